@@ -0,0 +1,70 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use pin_array::PinArray;
+use std::hint::black_box;
+
+const SIZE: usize = 4096;
+
+fn iter(c: &mut Criterion) {
+    let arr = PinArray::new(core::array::from_fn::<u64, SIZE, _>(|i| i as u64));
+    let plain = core::array::from_fn::<u64, SIZE, _>(|i| i as u64);
+
+    let mut g = c.benchmark_group("iter");
+    g.bench_function("pin_array", |b| {
+        b.iter(|| black_box(&arr).iter().sum::<u64>())
+    });
+    g.bench_function("slice", |b| {
+        b.iter(|| black_box(&plain).iter().sum::<u64>())
+    });
+    g.finish();
+
+    let mut g = c.benchmark_group("iter_rev");
+    g.bench_function("pin_array", |b| {
+        b.iter(|| black_box(&arr).iter().rev().fold(0u64, |a, e| a ^ e))
+    });
+    g.bench_function("slice", |b| {
+        b.iter(|| black_box(&plain).iter().rev().fold(0u64, |a, e| a ^ e))
+    });
+    g.finish();
+}
+
+fn iter_mut(c: &mut Criterion) {
+    let mut arr = Box::pin(PinArray::new([0u64; SIZE]));
+    let mut plain = Box::new([0u64; SIZE]);
+
+    let mut g = c.benchmark_group("iter_mut");
+    g.bench_function("pin_array", |b| {
+        b.iter(|| {
+            for mut e in black_box(arr.as_mut()).iter_mut() {
+                *e += 1;
+            }
+        })
+    });
+    g.bench_function("slice", |b| {
+        b.iter(|| {
+            for e in black_box(&mut plain).iter_mut() {
+                *e += 1;
+            }
+        })
+    });
+    g.finish();
+
+    let mut g = c.benchmark_group("iter_mut_step_by");
+    g.bench_function("pin_array", |b| {
+        b.iter(|| {
+            for mut e in black_box(arr.as_mut()).iter_mut().step_by(7) {
+                *e += 1;
+            }
+        })
+    });
+    g.bench_function("slice", |b| {
+        b.iter(|| {
+            for e in black_box(&mut plain).iter_mut().step_by(7) {
+                *e += 1;
+            }
+        })
+    });
+    g.finish();
+}
+
+criterion_group!(benches, iter, iter_mut);
+criterion_main!(benches);
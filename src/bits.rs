@@ -0,0 +1,54 @@
+//! Helpers for the fixed size bitsets used to track slots
+
+/// Number of slots tracked by each word of a bitset
+///
+/// Words are `u32` so that the atomic bitsets only need 32 bit atomics, which more targets have
+/// than 64 bit ones
+pub(crate) const WORD_BITS: usize = 32;
+
+/// Get the word `idx` is in and its mask within that word
+pub(crate) const fn locate(idx: usize) -> (usize, u32) {
+    (idx / WORD_BITS, 1 << (idx % WORD_BITS))
+}
+
+/// Get the word at `word` of a bitset with every one of `len` slots set
+pub(crate) const fn full_word(word: usize, len: usize) -> u32 {
+    let start = word * WORD_BITS;
+    if start >= len {
+        0
+    } else if len - start >= WORD_BITS {
+        u32::MAX
+    } else {
+        (1 << (len - start)) - 1
+    }
+}
+
+/// Iterate over the indices of the set bits in `word`, lowest first
+pub(crate) fn ones(mut word: u32) -> impl Iterator<Item = usize> {
+    core::iter::from_fn(move || {
+        if word == 0 {
+            None
+        } else {
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(bit)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{full_word, locate, ones};
+
+    #[test]
+    fn words() {
+        assert_eq!(locate(0), (0, 1));
+        assert_eq!(locate(33), (1, 2));
+        assert_eq!(full_word(0, 3), 0b111);
+        assert_eq!(full_word(0, 40), u32::MAX);
+        assert_eq!(full_word(1, 40), 0xff);
+        assert_eq!(full_word(2, 40), 0);
+        assert!(ones(0b1010_0001).eq([0, 5, 7]));
+        assert!(ones(u32::MAX).eq(0..32));
+    }
+}
@@ -0,0 +1,209 @@
+//! Minimal single threaded executor for a [`PinArray`] of tasks
+//!
+//! This is intended for places like firmware and test harnesses where there is a fixed set of
+//! tasks and no allocator. Each task gets a waker backed by a flag in a `'static` [`TaskFlags`],
+//! so wakers may be freely cloned and woken from interrupts or other threads.
+//!
+//! ```
+//! # use core::{future::ready, pin::pin};
+//! # use pin_array::{executor::{self, TaskFlags}, PinArray};
+//! static FLAGS: TaskFlags<2> = TaskFlags::new();
+//!
+//! let tasks = pin!(PinArray::new([ready(()), ready(())]));
+//! executor::run(tasks, &FLAGS);
+//! ```
+
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use crate::PinArray;
+
+/// Per-task wake flags for [`run`] and [`run_with_idle`]
+///
+/// These are intended to be put in a `static`, the wakers handed to the tasks point directly at
+/// them so they must outlive any clone of a waker. A set of flags should only be used by one
+/// executor at a time.
+pub struct TaskFlags<const N: usize> {
+    woken: [AtomicBool; N],
+}
+
+impl<const N: usize> Default for TaskFlags<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> TaskFlags<N> {
+    /// Create a new set of flags
+    pub const fn new() -> Self {
+        Self {
+            woken: [const { AtomicBool::new(false) }; N],
+        }
+    }
+
+    fn waker(&'static self, idx: usize) -> Waker {
+        let flag: &'static AtomicBool = &self.woken[idx];
+        // SAFETY: the flag is 'static and the vtable functions only ever treat the data as
+        // `&AtomicBool`
+        unsafe { Waker::from_raw(RawWaker::new(core::ptr::from_ref(flag).cast(), &VTABLE)) }
+    }
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_raw, wake_raw, wake_raw, drop_raw);
+
+unsafe fn clone_raw(data: *const ()) -> RawWaker {
+    RawWaker::new(data, &VTABLE)
+}
+
+unsafe fn wake_raw(data: *const ()) {
+    unsafe { &*data.cast::<AtomicBool>() }.store(true, Ordering::Release);
+}
+
+unsafe fn drop_raw(_: *const ()) {}
+
+/// Run every task to completion, spinning while none of them are ready
+///
+/// See [`run_with_idle`] to do something else while waiting
+pub fn run<F: Future<Output = ()>, const N: usize>(
+    tasks: Pin<&mut PinArray<F, N>>,
+    flags: &'static TaskFlags<N>,
+) {
+    run_with_idle(tasks, flags, core::hint::spin_loop)
+}
+
+/// Run every task to completion, calling `idle` whenever none of them are ready
+///
+/// Every task is polled once to start with, after that a task is only polled again once its
+/// waker has been woken. `idle` is a good place to wait for an interrupt or yield to an
+/// operating system.
+///
+/// ```
+/// # use core::{cell::Cell, future::poll_fn, pin::pin, task::{Poll, Waker}};
+/// # use pin_array::{executor::{self, TaskFlags}, PinArray};
+/// static FLAGS: TaskFlags<1> = TaskFlags::new();
+///
+/// let irq = Cell::new(false);
+/// let waker = Cell::new(None::<Waker>);
+/// let task = poll_fn(|cx| {
+///     if irq.get() {
+///         Poll::Ready(())
+///     } else {
+///         waker.set(Some(cx.waker().clone()));
+///         Poll::Pending
+///     }
+/// });
+/// executor::run_with_idle(pin!(PinArray::new([task])), &FLAGS, || {
+///     // pretend an interrupt fired while we were waiting
+///     irq.set(true);
+///     if let Some(w) = waker.take() {
+///         w.wake();
+///     }
+/// });
+/// ```
+pub fn run_with_idle<F: Future<Output = ()>, const N: usize>(
+    mut tasks: Pin<&mut PinArray<F, N>>,
+    flags: &'static TaskFlags<N>,
+    mut idle: impl FnMut(),
+) {
+    for flag in &flags.woken {
+        flag.store(true, Ordering::Relaxed);
+    }
+    let mut done = [false; N];
+    let mut remaining = N;
+    while remaining > 0 {
+        let mut polled = false;
+        for (i, task) in tasks.as_mut().iter_mut().enumerate() {
+            if done[i] || !flags.woken[i].swap(false, Ordering::Acquire) {
+                continue;
+            }
+            polled = true;
+            let waker = flags.waker(i);
+            if let Poll::Ready(()) = task.poll(&mut Context::from_waker(&waker)) {
+                done[i] = true;
+                remaining -= 1;
+            }
+        }
+        if !polled {
+            idle();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        cell::Cell,
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use crate::{future::tests::Countdown, PinArray};
+
+    use super::{run, run_with_idle, TaskFlags};
+
+    #[test]
+    fn runs_all_to_completion() {
+        static FLAGS: TaskFlags<3> = TaskFlags::new();
+        let mut tasks = pin!(PinArray::new([
+            Countdown::new(3, ()),
+            Countdown::new(0, ()),
+            Countdown::new(5, ()),
+        ]));
+        run(tasks.as_mut(), &FLAGS);
+        assert_eq!(tasks.iter().map(|t| t.polls).sum::<u32>(), 4 + 1 + 6);
+    }
+
+    /// Task which waits for `ready` to be set, stashing its waker
+    struct Wait<'a> {
+        ready: &'a Cell<bool>,
+        waker: &'a Cell<Option<Waker>>,
+        polls: &'a Cell<u32>,
+    }
+
+    impl Future for Wait<'_> {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            self.polls.set(self.polls.get() + 1);
+            if self.ready.get() {
+                Poll::Ready(())
+            } else {
+                self.waker.set(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn idle_until_woken() {
+        static FLAGS: TaskFlags<2> = TaskFlags::new();
+        let ready = [Cell::new(false), Cell::new(false)];
+        let wakers = [Cell::new(None), Cell::new(None)];
+        let polls = [Cell::new(0), Cell::new(0)];
+        let tasks = pin!(PinArray::new(core::array::from_fn::<_, 2, _>(|i| Wait {
+            ready: &ready[i],
+            waker: &wakers[i],
+            polls: &polls[i],
+        })));
+        let mut idles = 0;
+        run_with_idle(tasks, &FLAGS, || {
+            // wake one task per idle, only the woken one should be polled
+            ready[idles].set(true);
+            wakers[idles].take().unwrap().wake();
+            idles += 1;
+        });
+        assert_eq!(idles, 2);
+        assert_eq!(polls.each_ref().map(Cell::get), [2, 2]);
+    }
+
+    #[test]
+    fn empty() {
+        static FLAGS: TaskFlags<0> = TaskFlags::new();
+        run(pin!(PinArray::<Countdown<()>, 0>::new([])), &FLAGS);
+    }
+}
@@ -0,0 +1,985 @@
+//! Combinators for [`PinArray`]s of futures
+//!
+//! These are all allocation free, the futures are polled in place inside the array
+
+use core::{
+    cell::UnsafeCell,
+    future::Future,
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use crate::{
+    bits::{self, WORD_BITS},
+    rotate::Rotation,
+    PinArray,
+};
+
+/// A future which may have completed, holding its output until it is taken
+pub(crate) enum MaybeDone<F: Future> {
+    Future(F),
+    Done(F::Output),
+    Gone,
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Poll the inner future if it is still running, returns `true` if it has completed
+    ///
+    /// # Panics
+    /// If the output has already been taken
+    pub(crate) fn poll_done(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            Self::Future(f) => match unsafe { Pin::new_unchecked(f) }.poll(cx) {
+                Poll::Ready(v) => {
+                    // drops the future in place
+                    *this = Self::Done(v);
+                    true
+                }
+                Poll::Pending => false,
+            },
+            Self::Done(_) => true,
+            Self::Gone => panic!("MaybeDone polled after value taken"),
+        }
+    }
+
+    /// Drop the future or output in place
+    pub(crate) fn clear(self: Pin<&mut Self>) {
+        unsafe { *self.get_unchecked_mut() = Self::Gone };
+    }
+
+    pub(crate) fn is_future(&self) -> bool {
+        matches!(self, Self::Future(_))
+    }
+
+    /// Take the output if the future has completed
+    pub(crate) fn take_output(self: Pin<&mut Self>) -> Option<F::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            Self::Done(_) => match core::mem::replace(this, Self::Gone) {
+                Self::Done(v) => Some(v),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Future for [`PinArray::join`]
+///
+/// Resolves to the outputs of all the futures once every one of them has completed
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Join<F: Future, const N: usize> {
+    futs: PinArray<MaybeDone<F>, N>,
+}
+
+impl<F: Future, const N: usize> PinArray<F, N> {
+    /// Join all the futures in this array, resolving to an array of their outputs
+    ///
+    /// Every pending future is polled each time the join is polled, and the outputs are stored in
+    /// place until all of them have completed
+    ///
+    /// ```
+    /// # use core::{future::{ready, Future}, pin::pin, task::{Context, Poll, Waker}};
+    /// # use pin_array::PinArray;
+    /// let j = pin!(PinArray::new([ready(1), ready(2)]).join());
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(j.poll(&mut cx), Poll::Ready([1, 2]));
+    /// ```
+    pub fn join(self) -> Join<F, N> {
+        Join {
+            futs: PinArray::new(self.elements.map(MaybeDone::Future)),
+        }
+    }
+}
+
+impl<F: Future, const N: usize> Future for Join<F, N> {
+    type Output = [F::Output; N];
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut futs = unsafe { self.map_unchecked_mut(|s| &mut s.futs) };
+        let mut done = true;
+        for f in futs.as_mut().iter_mut() {
+            done &= f.poll_done(cx);
+        }
+        if done {
+            Poll::Ready(core::array::from_fn(|i| {
+                futs.as_mut()
+                    .get_pin(i)
+                    .and_then(MaybeDone::take_output)
+                    .unwrap()
+            }))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future for [`PinArray::try_join`]
+///
+/// Resolves to the outputs of all the futures once every one of them has succeeded, or the first
+/// error
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryJoin<F: Future, const N: usize> {
+    futs: PinArray<MaybeDone<F>, N>,
+}
+
+impl<T, E, F: Future<Output = Result<T, E>>, const N: usize> PinArray<F, N> {
+    /// Join all the fallible futures in this array, short circuiting on the first error
+    ///
+    /// As soon as any future fails the rest of the futures, and any outputs already stored, are
+    /// dropped in place and not polled again
+    ///
+    /// ```
+    /// # use core::{future::{ready, Future}, pin::pin, task::{Context, Poll, Waker}};
+    /// # use pin_array::PinArray;
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let j = pin!(PinArray::new([ready(Ok::<_, ()>(1)), ready(Ok(2))]).try_join());
+    /// assert_eq!(j.poll(&mut cx), Poll::Ready(Ok([1, 2])));
+    /// let j = pin!(PinArray::new([ready(Ok(1)), ready(Err("oh no"))]).try_join());
+    /// assert_eq!(j.poll(&mut cx), Poll::Ready(Err("oh no")));
+    /// ```
+    pub fn try_join(self) -> TryJoin<F, N> {
+        TryJoin {
+            futs: PinArray::new(self.elements.map(MaybeDone::Future)),
+        }
+    }
+}
+
+impl<T, E, F: Future<Output = Result<T, E>>, const N: usize> Future for TryJoin<F, N> {
+    type Output = Result<[T; N], E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut futs = unsafe { self.map_unchecked_mut(|s| &mut s.futs) };
+        let mut done = true;
+        for mut f in futs.as_mut().iter_mut() {
+            if !f.as_mut().poll_done(cx) {
+                done = false;
+            } else if matches!(&*f, MaybeDone::Done(Err(_))) {
+                let err = f.take_output().and_then(Result::err).unwrap();
+                for f in futs.as_mut().iter_mut() {
+                    f.clear();
+                }
+                return Poll::Ready(Err(err));
+            }
+        }
+        if done {
+            Poll::Ready(Ok(core::array::from_fn(|i| {
+                futs.as_mut()
+                    .get_pin(i)
+                    .and_then(MaybeDone::take_output)
+                    .and_then(Result::ok)
+                    .unwrap()
+            })))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+const WAITING: u8 = 0;
+const REGISTERING: u8 = 0b01;
+const WAKING: u8 = 0b10;
+
+/// A waker which can be registered and woken concurrently, the same as `futures`' `AtomicWaker`
+struct AtomicWaker {
+    state: AtomicU8,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// SAFETY: access to `waker` is serialised by `state`
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Register `waker` to be woken by the next call to [`AtomicWaker::wake`]
+    fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+            .unwrap_or_else(|s| s)
+        {
+            WAITING => {
+                // SAFETY: holding `REGISTERING` gives us exclusive access to the waker
+                let slot = unsafe { &mut *self.waker.get() };
+                if !slot.as_ref().is_some_and(|w| w.will_wake(waker)) {
+                    *slot = Some(waker.clone());
+                }
+                let unlocked = self.state.compare_exchange(
+                    REGISTERING,
+                    WAITING,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+                if unlocked.is_err() {
+                    // woken while registering, the wake is ours to deliver
+                    let w = slot.take();
+                    self.state.swap(WAITING, Ordering::AcqRel);
+                    if let Some(w) = w {
+                        w.wake();
+                    }
+                }
+            }
+            WAKING => waker.wake_by_ref(),
+            // another registration is in progress, which only happens if the flags are shared
+            _ => {}
+        }
+    }
+
+    /// Wake and unregister the waker, if there is one
+    fn wake(&self) {
+        if self.state.fetch_or(WAKING, Ordering::AcqRel) == WAITING {
+            // SAFETY: holding `WAKING` gives us exclusive access to the waker
+            let w = unsafe { (*self.waker.get()).take() };
+            self.state.fetch_and(!WAKING, Ordering::Release);
+            if let Some(w) = w {
+                w.wake();
+            }
+        }
+    }
+}
+
+/// Wake flags for [`PinArray::join_woken`]
+///
+/// Each future in the join gets a waker pointing at its own slot in these flags, and waking it
+/// sets the slot's bit before waking the task polling the join. Futures commonly clone their
+/// waker to hand to a reactor or channel, and those clones can outlive the join. Pinning only
+/// keeps the join at the same address until it is dropped, so wakers pointing inside it would
+/// dangle once it is gone. Instead, like [`TaskFlags`](crate::executor::TaskFlags), the flags go
+/// in a `static` and every clone still knows which slot it came from.
+///
+/// Readiness is stored as a bitset of `W` 32 bit words, which must be enough for `N` slots. `W`
+/// defaults to 1 which covers up to 32 futures, larger joins need to specify it:
+///
+/// ```
+/// # use pin_array::future::JoinFlags;
+/// static SMALL: JoinFlags<8> = JoinFlags::new();
+/// static LARGE: JoinFlags<100, 4> = JoinFlags::new();
+/// ```
+///
+/// A set of flags can only be used by one join at a time, [`PinArray::join_woken`] panics if they
+/// are already in use. They are released when the join is dropped, so if it is leaked with
+/// [`core::mem::forget`] they can never be used again.
+#[repr(C)]
+pub struct JoinFlags<const N: usize, const W: usize = 1> {
+    /// Each slot's waker points at its own index in here, which leads back to the flags.
+    /// This must stay the first field.
+    slots: [usize; N],
+    woken: [AtomicU32; W],
+    parent: AtomicWaker,
+    in_use: AtomicBool,
+}
+
+impl<const N: usize, const W: usize> Default for JoinFlags<N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const W: usize> JoinFlags<N, W> {
+    const VTABLE: &'static RawWakerVTable = &RawWakerVTable::new(
+        Self::clone_raw,
+        Self::wake_raw,
+        Self::wake_raw,
+        Self::drop_raw,
+    );
+
+    /// Create a new set of flags
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N <= W * WORD_BITS,
+                "JoinFlags needs W to be at least N / 32 rounded up"
+            )
+        };
+        let mut slots = [0; N];
+        let mut i = 0;
+        while i < N {
+            slots[i] = i;
+            i += 1;
+        }
+        Self {
+            slots,
+            woken: [const { AtomicU32::new(0) }; W],
+            parent: AtomicWaker::new(),
+            in_use: AtomicBool::new(false),
+        }
+    }
+
+    /// Claim the flags for a new join and mark every slot as woken, so it polls all of its
+    /// futures to start with
+    ///
+    /// # Panics
+    /// If the flags are already in use by another join
+    fn claim(&self) {
+        assert!(
+            !self.in_use.swap(true, Ordering::Acquire),
+            "JoinFlags are already in use by another join"
+        );
+        for (i, word) in self.woken.iter().enumerate() {
+            word.store(bits::full_word(i, N), Ordering::Relaxed);
+        }
+    }
+
+    fn waker(&'static self, idx: usize) -> Waker {
+        // derived from the whole of `self` so that the flags can be reached from the slot
+        let slot = unsafe { ptr::from_ref(self).cast::<usize>().add(idx) };
+        // SAFETY: the flags are 'static and the vtable functions only ever treat the data as a
+        // pointer into `slots`
+        unsafe { Waker::from_raw(RawWaker::new(slot.cast(), Self::VTABLE)) }
+    }
+
+    /// # Safety
+    /// `data` must have come from [`JoinFlags::waker`]
+    unsafe fn from_raw(data: *const ()) -> (&'static Self, usize) {
+        let slot = data.cast::<usize>();
+        let idx = unsafe { *slot };
+        (unsafe { &*slot.sub(idx).cast::<Self>() }, idx)
+    }
+
+    unsafe fn clone_raw(data: *const ()) -> RawWaker {
+        RawWaker::new(data, Self::VTABLE)
+    }
+
+    unsafe fn wake_raw(data: *const ()) {
+        let (me, idx) = unsafe { Self::from_raw(data) };
+        let (word, mask) = bits::locate(idx);
+        me.woken[word].fetch_or(mask, Ordering::Release);
+        me.parent.wake();
+    }
+
+    unsafe fn drop_raw(_: *const ()) {}
+}
+
+/// Future for [`PinArray::join_woken`]
+///
+/// Resolves to the outputs of all the futures once every one of them has completed
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinWoken<F: Future, const N: usize, const W: usize = 1> {
+    futs: PinArray<MaybeDone<F>, N>,
+    flags: &'static JoinFlags<N, W>,
+    pending: usize,
+}
+
+impl<F: Future, const N: usize> PinArray<F, N> {
+    /// Join all the futures in this array, only re-polling the ones which have been woken
+    ///
+    /// This resolves to the same thing as [`PinArray::join`] but gives each future its own
+    /// waker, backed by a slot in `flags`, and records which ones have been woken. Each poll of
+    /// the join then only polls those futures, rather than all `N` of them. Clones of the
+    /// wakers wake the same slot, so futures which register their waker with a reactor are
+    /// handled too.
+    ///
+    /// # Panics
+    /// If `flags` are already in use by another join which has not been dropped yet
+    ///
+    /// ```
+    /// # use core::{future::{ready, Future}, pin::pin, task::{Context, Poll, Waker}};
+    /// # use pin_array::{future::JoinFlags, PinArray};
+    /// static FLAGS: JoinFlags<2> = JoinFlags::new();
+    ///
+    /// let j = pin!(PinArray::new([ready(1), ready(2)]).join_woken(&FLAGS));
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(j.poll(&mut cx), Poll::Ready([1, 2]));
+    /// ```
+    pub fn join_woken<const W: usize>(self, flags: &'static JoinFlags<N, W>) -> JoinWoken<F, N, W> {
+        flags.claim();
+        JoinWoken {
+            futs: PinArray::new(self.elements.map(MaybeDone::Future)),
+            flags,
+            pending: N,
+        }
+    }
+}
+
+impl<F: Future, const N: usize, const W: usize> Drop for JoinWoken<F, N, W> {
+    fn drop(&mut self) {
+        self.flags.in_use.store(false, Ordering::Release);
+    }
+}
+
+impl<F: Future, const N: usize, const W: usize> Future for JoinWoken<F, N, W> {
+    type Output = [F::Output; N];
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let flags = this.flags;
+        // registered before taking the woken slots so that a wake from here on reaches us
+        flags.parent.register(cx.waker());
+        let mut futs = unsafe { Pin::new_unchecked(&mut this.futs) };
+        for (w, word) in flags.woken.iter().enumerate() {
+            for bit in bits::ones(word.swap(0, Ordering::Acquire)) {
+                let i = w * WORD_BITS + bit;
+                let Some(f) = futs.as_mut().get_pin(i) else {
+                    continue;
+                };
+                if f.is_future() && f.poll_done(&mut Context::from_waker(&flags.waker(i))) {
+                    this.pending -= 1;
+                }
+            }
+        }
+        if this.pending == 0 {
+            Poll::Ready(core::array::from_fn(|i| {
+                futs.as_mut()
+                    .get_pin(i)
+                    .and_then(MaybeDone::take_output)
+                    .expect("JoinWoken polled after completion")
+            }))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future for [`PinArray::select`]
+///
+/// Resolves to the index and output of the first future to complete
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Select<F, const N: usize> {
+    futs: PinArray<F, N>,
+    done: [bool; N],
+    remaining: usize,
+}
+
+/// Future for [`PinArray::select_fair`]
+///
+/// Resolves to the index and output of the first future to complete
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SelectFair<F, const N: usize> {
+    select: Select<F, N>,
+    rotation: Rotation,
+}
+
+impl<F: Future, const N: usize> PinArray<F, N> {
+    /// Race all the futures in this array, resolving to the index and output of the first one to
+    /// complete
+    ///
+    /// The futures are polled in index order, so earlier ones are favoured if several are ready
+    /// at once, see [`PinArray::select_fair`] to avoid this.
+    ///
+    /// The rest of the futures stay pinned in place inside the returned future. Polling it again
+    /// after it completes races the ones which have not completed yet, the completed ones are
+    /// never polled again.
+    ///
+    /// # Panics
+    /// If polled again after every future has completed
+    ///
+    /// ```
+    /// # use core::{future::{ready, Future}, pin::pin, task::{Context, Poll, Waker}};
+    /// # use pin_array::PinArray;
+    /// let mut s = pin!(PinArray::new([ready(1), ready(2)]).select());
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((0, 1)));
+    /// // the other future is still there to be raced again
+    /// assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((1, 2)));
+    /// ```
+    pub fn select(self) -> Select<F, N> {
+        const { assert!(N > 0, "cannot select over an empty PinArray") };
+        Select {
+            futs: self,
+            done: [false; N],
+            remaining: N,
+        }
+    }
+
+    /// Race all the futures in this array fairly, resolving to the index and output of the first
+    /// one to complete
+    ///
+    /// This is the same as [`PinArray::select`] except that each poll starts from the future
+    /// after the one the previous poll started from, rather than always from the first
+    ///
+    /// ```
+    /// # use core::{future::{ready, Future}, pin::pin, task::{Context, Poll, Waker}};
+    /// # use pin_array::PinArray;
+    /// let mut s = pin!(PinArray::new([ready(0), ready(1)]).select_fair());
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((0, 0)));
+    /// assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((1, 1)));
+    /// ```
+    pub fn select_fair(self) -> SelectFair<F, N> {
+        SelectFair {
+            select: self.select(),
+            rotation: Rotation::new(),
+        }
+    }
+}
+
+impl<F: Future, const N: usize> Select<F, N> {
+    /// Get a pinned reference to a future which has not completed yet
+    ///
+    /// Returns `None` if `idx` is out of bounds or the future has already completed
+    pub fn get_pin(self: Pin<&mut Self>, idx: usize) -> Option<Pin<&mut F>> {
+        let this = unsafe { self.get_unchecked_mut() };
+        if *this.done.get(idx)? {
+            return None;
+        }
+        unsafe { Pin::new_unchecked(&mut this.futs) }.get_pin(idx)
+    }
+
+    /// Poll the futures which have not completed yet in the order given by `order`
+    fn poll_in(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        order: impl Iterator<Item = usize>,
+    ) -> Poll<(usize, F::Output)> {
+        let this = unsafe { self.get_unchecked_mut() };
+        assert!(
+            this.remaining > 0,
+            "Select polled after every future completed"
+        );
+        let mut futs = unsafe { Pin::new_unchecked(&mut this.futs) };
+        for i in order {
+            if this.done[i] {
+                continue;
+            }
+            if let Poll::Ready(v) = futs.as_mut().get_pin(i).unwrap().poll(cx) {
+                this.done[i] = true;
+                this.remaining -= 1;
+                return Poll::Ready((i, v));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<F: Future, const N: usize> SelectFair<F, N> {
+    /// Get a pinned reference to a future which has not completed yet
+    ///
+    /// See [`Select::get_pin`]
+    pub fn get_pin(self: Pin<&mut Self>, idx: usize) -> Option<Pin<&mut F>> {
+        unsafe { self.map_unchecked_mut(|s| &mut s.select) }.get_pin(idx)
+    }
+}
+
+impl<F: Future, const N: usize> Future for Select<F, N> {
+    type Output = (usize, F::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_in(cx, 0..N)
+    }
+}
+
+impl<F: Future, const N: usize> Future for SelectFair<F, N> {
+    type Output = (usize, F::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let order = this.rotation.round(N);
+        unsafe { Pin::new_unchecked(&mut this.select) }.poll_in(cx, order)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use core::{
+        cell::Cell,
+        future::Future,
+        marker::PhantomPinned,
+        mem::ManuallyDrop,
+        pin::{pin, Pin},
+        ptr,
+        sync::atomic::{AtomicU32, Ordering},
+        task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    };
+
+    use super::JoinFlags;
+    use crate::PinArray;
+
+    /// Future which is pending for `remaining` polls, then resolves to `value`
+    pub(crate) struct Countdown<T> {
+        pub(crate) remaining: u32,
+        pub(crate) polls: u32,
+        pub(crate) value: Option<T>,
+        _p: PhantomPinned,
+    }
+
+    impl<T> Countdown<T> {
+        pub(crate) fn new(remaining: u32, value: T) -> Self {
+            Self {
+                remaining,
+                polls: 0,
+                value: Some(value),
+                _p: PhantomPinned,
+            }
+        }
+    }
+
+    impl<T> Future for Countdown<T> {
+        type Output = T;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = unsafe { self.get_unchecked_mut() };
+            this.polls += 1;
+            if this.remaining == 0 {
+                Poll::Ready(this.value.take().expect("polled after completion"))
+            } else {
+                this.remaining -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn join_waits_for_all() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut j = pin!(PinArray::new([
+            Countdown::new(2, 'a'),
+            Countdown::new(0, 'b'),
+            Countdown::new(1, 'c'),
+        ])
+        .join());
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Ready(['a', 'b', 'c']));
+    }
+
+    #[test]
+    fn join_empty() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let j = pin!(PinArray::<Countdown<u8>, 0>::new([]).join());
+        assert_eq!(j.poll(&mut cx), Poll::Ready([]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn join_poll_after_completion() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut j = pin!(PinArray::new([Countdown::new(0, 1)]).join());
+        let _ = j.as_mut().poll(&mut cx);
+        let _ = j.as_mut().poll(&mut cx);
+    }
+
+    /// Future which counts its polls and is only ready once `ready` is set
+    pub(crate) struct Gate<'a> {
+        pub(crate) polls: &'a Cell<u32>,
+        pub(crate) ready: &'a Cell<bool>,
+        /// Clone the waker into here on every poll
+        pub(crate) stash: Option<&'a Cell<Option<Waker>>>,
+        /// Wake with `wake_by_ref` on every poll
+        pub(crate) wake_self: bool,
+    }
+
+    impl<'a> Gate<'a> {
+        pub(crate) fn new(polls: &'a Cell<u32>, ready: &'a Cell<bool>) -> Self {
+            Self {
+                polls,
+                ready,
+                stash: None,
+                wake_self: false,
+            }
+        }
+    }
+
+    impl Future for Gate<'_> {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.polls.set(self.polls.get() + 1);
+            if self.ready.get() {
+                Poll::Ready(self.polls.get())
+            } else {
+                if let Some(stash) = self.stash {
+                    stash.set(Some(cx.waker().clone()));
+                }
+                if self.wake_self {
+                    cx.waker().wake_by_ref();
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    static COUNT_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |p| RawWaker::new(p, &COUNT_WAKER_VTABLE),
+        count_wake,
+        count_wake,
+        |_| {},
+    );
+
+    fn count_wake(p: *const ()) {
+        unsafe { &*p.cast::<AtomicU32>() }.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Waker which counts how many times it is woken
+    ///
+    /// This uses a `static` vtable so that `will_wake` is reliable, even under miri
+    pub(crate) fn count_waker(count: &AtomicU32) -> ManuallyDrop<Waker> {
+        ManuallyDrop::new(unsafe {
+            Waker::from_raw(RawWaker::new(
+                ptr::from_ref(count).cast(),
+                &COUNT_WAKER_VTABLE,
+            ))
+        })
+    }
+
+    #[test]
+    fn join_woken_only_polls_woken() {
+        static FLAGS: JoinFlags<3> = JoinFlags::new();
+        let wakes = AtomicU32::new(0);
+        let parent = count_waker(&wakes);
+        let mut cx = Context::from_waker(&parent);
+        let polls = [Cell::new(0), Cell::new(0), Cell::new(0)];
+        let never = Cell::new(false);
+        let mut j = pin!(PinArray::new([
+            Gate::new(&polls[0], &never),
+            Gate {
+                wake_self: true,
+                ..Gate::new(&polls[1], &never)
+            },
+            Gate::new(&polls[2], &never),
+        ])
+        .join_woken(&FLAGS));
+        for _ in 0..4 {
+            assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        }
+        assert_eq!(polls.each_ref().map(Cell::get), [1, 4, 1]);
+    }
+
+    #[test]
+    fn join_woken_cloned_wakers() {
+        static FLAGS: JoinFlags<8> = JoinFlags::new();
+        let wakes = AtomicU32::new(0);
+        let parent = count_waker(&wakes);
+        let mut cx = Context::from_waker(&parent);
+        let polls: [_; 8] = core::array::from_fn(|_| Cell::new(0));
+        let stashes: [_; 8] = core::array::from_fn(|_| Cell::new(None));
+        let never = Cell::new(false);
+        // these register a clone of their waker on every poll, like a reactor or channel would
+        let mut j = pin!(PinArray::new(core::array::from_fn::<_, 8, _>(|i| Gate {
+            stash: Some(&stashes[i]),
+            ..Gate::new(&polls[i], &never)
+        }))
+        .join_woken(&FLAGS));
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        for n in 1..=10 {
+            stashes[0].take().unwrap().wake();
+            assert_eq!(wakes.load(Ordering::Relaxed), n);
+            assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        }
+        assert_eq!(polls.each_ref().map(Cell::get), [11, 1, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn join_woken_new_parent() {
+        static FLAGS: JoinFlags<2> = JoinFlags::new();
+        let wakes = [AtomicU32::new(0), AtomicU32::new(0)];
+        let parents = [count_waker(&wakes[0]), count_waker(&wakes[1])];
+        let polls = [Cell::new(0), Cell::new(0)];
+        let ready = [Cell::new(false), Cell::new(false)];
+        let stash = Cell::new(None);
+        let mut j = pin!(PinArray::new([
+            Gate {
+                stash: Some(&stash),
+                ..Gate::new(&polls[0], &ready[0])
+            },
+            Gate::new(&polls[1], &ready[1]),
+        ])
+        .join_woken(&FLAGS));
+        assert_eq!(
+            j.as_mut().poll(&mut Context::from_waker(&parents[0])),
+            Poll::Pending
+        );
+
+        // the join moved to another task, a waker stashed before then wakes the new one
+        let stashed = stash.take().unwrap();
+        assert_eq!(
+            j.as_mut().poll(&mut Context::from_waker(&parents[1])),
+            Poll::Pending
+        );
+        ready[0].set(true);
+        stashed.wake();
+        assert_eq!(wakes.each_ref().map(|w| w.load(Ordering::Relaxed)), [0, 1]);
+        assert_eq!(
+            j.as_mut().poll(&mut Context::from_waker(&parents[1])),
+            Poll::Pending
+        );
+        assert_eq!(polls.each_ref().map(Cell::get), [2, 1]);
+    }
+
+    #[test]
+    fn join_woken_completes() {
+        static FLAGS: JoinFlags<3> = JoinFlags::new();
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut j = pin!(PinArray::new([
+            Countdown::new(2, 'a'),
+            Countdown::new(0, 'b'),
+            Countdown::new(1, 'c'),
+        ])
+        .join_woken(&FLAGS));
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Ready(['a', 'b', 'c']));
+    }
+
+    #[test]
+    fn join_woken_many_words() {
+        static FLAGS: JoinFlags<40, 2> = JoinFlags::new();
+        let mut cx = Context::from_waker(Waker::noop());
+        let j = pin!(PinArray::new(core::array::from_fn::<_, 40, _>(|i| {
+            Countdown::new(0, i)
+        }))
+        .join_woken(&FLAGS));
+        assert_eq!(j.poll(&mut cx), Poll::Ready(core::array::from_fn(|i| i)));
+    }
+
+    #[test]
+    #[should_panic = "already in use"]
+    fn join_woken_flags_in_use() {
+        static FLAGS: JoinFlags<1> = JoinFlags::new();
+        let first = PinArray::new([Countdown::new(0, 1)]).join_woken(&FLAGS);
+        drop(first);
+        let _second = PinArray::new([Countdown::new(0, 2)]).join_woken(&FLAGS);
+        let _third = PinArray::new([Countdown::new(0, 3)]).join_woken(&FLAGS);
+    }
+
+    #[test]
+    #[should_panic]
+    fn join_woken_poll_after_completion() {
+        static FLAGS: JoinFlags<1> = JoinFlags::new();
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut j = pin!(PinArray::new([Countdown::new(0, 1)]).join_woken(&FLAGS));
+        let _ = j.as_mut().poll(&mut cx);
+        let _ = j.as_mut().poll(&mut cx);
+    }
+
+    #[test]
+    fn select_first_ready() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let polls = [Cell::new(0), Cell::new(0), Cell::new(0)];
+        let ready = [Cell::new(false), Cell::new(false), Cell::new(false)];
+        let mut s = pin!(PinArray::new(core::array::from_fn::<_, 3, _>(|i| {
+            Gate::new(&polls[i], &ready[i])
+        }))
+        .select());
+        assert_eq!(s.as_mut().poll(&mut cx), Poll::Pending);
+        ready[2].set(true);
+        assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((2, 2)));
+        assert_eq!(polls.each_ref().map(Cell::get), [2, 2, 2]);
+        assert!(s.as_mut().get_pin(2).is_none());
+        assert!(s.as_mut().get_pin(1).is_some());
+
+        // the others are still pinned in place and can be raced again
+        ready[1].set(true);
+        assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((1, 3)));
+        assert_eq!(polls.each_ref().map(Cell::get), [3, 3, 2]);
+    }
+
+    #[test]
+    fn select_skips_completed() {
+        let mut cx = Context::from_waker(Waker::noop());
+        // `async` blocks panic if they are polled after completing
+        let mut s = pin!(PinArray::<_, 2>::from_fn(|i| async move { i }).select());
+        assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((0, 0)));
+        assert_eq!(s.as_mut().poll(&mut cx), Poll::Ready((1, 1)));
+
+        let mut s = pin!(PinArray::<_, 3>::from_fn(|i| async move { i }).select_fair());
+        let mut winners = [0; 3];
+        for w in &mut winners {
+            match s.as_mut().poll(&mut cx) {
+                Poll::Ready((i, _)) => *w = i,
+                Poll::Pending => panic!("all futures are ready"),
+            }
+        }
+        winners.sort_unstable();
+        assert_eq!(winners, [0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic = "Select polled after every future completed"]
+    fn select_poll_after_all_complete() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut s = pin!(PinArray::new([async {}]).select_fair());
+        let _ = s.as_mut().poll(&mut cx);
+        let _ = s.as_mut().poll(&mut cx);
+    }
+
+    #[test]
+    fn select_fair_rotates() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let polls = [Cell::new(0), Cell::new(0), Cell::new(0), Cell::new(0)];
+        let ready = Cell::new(true);
+        let mut s = pin!(PinArray::new(core::array::from_fn::<_, 4, _>(|i| {
+            Gate::new(&polls[i], &ready)
+        }))
+        .select_fair());
+        let mut winners = [0; 3];
+        for w in &mut winners {
+            match s.as_mut().poll(&mut cx) {
+                Poll::Ready((i, _)) => *w = i,
+                Poll::Pending => panic!("all futures are ready"),
+            }
+        }
+        assert_eq!(winners, [0, 1, 2]);
+        assert_eq!(polls.each_ref().map(Cell::get), [1, 1, 1, 0]);
+    }
+
+    /// Future which records when it is dropped
+    struct DropFlag<'a, F> {
+        inner: F,
+        dropped: &'a Cell<bool>,
+    }
+
+    impl<F> Drop for DropFlag<'_, F> {
+        fn drop(&mut self) {
+            self.dropped.set(true);
+        }
+    }
+
+    impl<F: Future> Future for DropFlag<'_, F> {
+        type Output = F::Output;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            unsafe { self.map_unchecked_mut(|s| &mut s.inner) }.poll(cx)
+        }
+    }
+
+    #[test]
+    fn try_join_ok() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut j = pin!(PinArray::new([
+            Countdown::new(1, Ok::<_, ()>('a')),
+            Countdown::new(0, Ok('b')),
+        ])
+        .try_join());
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Ready(Ok(['a', 'b'])));
+    }
+
+    #[test]
+    fn try_join_cancels_on_error() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let dropped = [Cell::new(false), Cell::new(false), Cell::new(false)];
+        let results = [Ok(0), Err("failed"), Ok(2)];
+        let countdowns = [0, 1, 5];
+        let mut j = pin!(PinArray::new(core::array::from_fn::<_, 3, _>(|i| {
+            DropFlag {
+                inner: Countdown::new(countdowns[i], results[i]),
+                dropped: &dropped[i],
+            }
+        }))
+        .try_join());
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Pending);
+        // the first has completed and been dropped, but the output is kept
+        assert_eq!(dropped.each_ref().map(Cell::get), [true, false, false]);
+        assert_eq!(j.as_mut().poll(&mut cx), Poll::Ready(Err("failed")));
+        assert_eq!(dropped.each_ref().map(Cell::get), [true, true, true]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn try_join_poll_after_error() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut j = pin!(PinArray::new([Countdown::new(0, Err::<(), _>(()))]).try_join());
+        let _ = j.as_mut().poll(&mut cx);
+        let _ = j.as_mut().poll(&mut cx);
+    }
+}
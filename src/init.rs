@@ -0,0 +1,390 @@
+use core::{convert::Infallible, mem::MaybeUninit, pin::Pin};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::PinArray;
+
+/// Drops the first `len` elements starting at `base` in reverse order, unless forgotten
+struct InitGuard<T> {
+    base: *mut T,
+    len: usize,
+}
+
+impl<T> Drop for InitGuard<T> {
+    fn drop(&mut self) {
+        while self.len > 0 {
+            self.len -= 1;
+            unsafe { self.base.add(self.len).drop_in_place() };
+        }
+    }
+}
+
+impl<T, const SIZE: usize> PinArray<T, SIZE> {
+    /// Initialise a `PinArray` in place, without the elements ever being moved
+    ///
+    /// `init` is called with the index and the pinned, uninitialised location of each element in
+    /// order. If it panics, the elements which were already initialised are dropped in reverse
+    /// order before the panic continues. See [`PinArray::try_init_pinned`] for initialisers which
+    /// can fail.
+    ///
+    /// # Safety
+    /// - `init` must fully initialise the element it is given before returning
+    /// - `MaybeUninit` never drops its contents, so the array must be dropped in place (e.g. with
+    ///   [`core::ptr::drop_in_place`]) before the memory behind `this` is reused or invalidated
+    ///
+    /// ```
+    /// # use core::{mem::MaybeUninit, pin::pin};
+    /// # use pin_array::PinArray;
+    /// let mut slot = pin!(MaybeUninit::<PinArray<usize, 3>>::uninit());
+    /// let p = unsafe {
+    ///     PinArray::init_pinned(slot.as_mut(), |i, e| {
+    ///         e.get_unchecked_mut().write(i * 2);
+    ///     })
+    /// };
+    /// assert_eq!(p.as_ref_array(), [&0, &2, &4]);
+    /// // `slot` is about to go out of scope, so the array has to be dropped in place first
+    /// unsafe { core::ptr::drop_in_place(p.get_unchecked_mut()) };
+    /// ```
+    pub unsafe fn init_pinned(
+        this: Pin<&mut MaybeUninit<Self>>,
+        mut init: impl FnMut(usize, Pin<&mut MaybeUninit<T>>),
+    ) -> Pin<&mut Self> {
+        let res = unsafe {
+            Self::try_init_pinned(this, |i, e| {
+                init(i, e);
+                Ok::<_, Infallible>(())
+            })
+        };
+        match res {
+            Ok(p) => p,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Initialise a `PinArray` in place with an initialiser which can fail
+    ///
+    /// This is the same as [`PinArray::init_pinned`], except that initialisation stops at the
+    /// first error. The elements which were already initialised are then dropped in reverse
+    /// order and the error is returned, leaving `this` uninitialised.
+    ///
+    /// # Safety
+    /// - `init` must fully initialise the element it is given if it returns `Ok`. If it returns
+    ///   `Err` the element is treated as uninitialised and is not dropped.
+    /// - `MaybeUninit` never drops its contents, so on success the array must be dropped in place
+    ///   (e.g. with [`core::ptr::drop_in_place`]) before the memory behind `this` is reused or
+    ///   invalidated
+    ///
+    /// ```
+    /// # use core::{mem::MaybeUninit, pin::pin};
+    /// # use pin_array::PinArray;
+    /// let mut slot = pin!(MaybeUninit::<PinArray<u8, 4>>::uninit());
+    /// let res = unsafe {
+    ///     PinArray::try_init_pinned(slot.as_mut(), |i, e| {
+    ///         e.get_unchecked_mut().write(u8::try_from(i * 100)?);
+    ///         Ok::<_, core::num::TryFromIntError>(())
+    ///     })
+    /// };
+    /// assert!(res.is_err());
+    /// ```
+    pub unsafe fn try_init_pinned<E>(
+        this: Pin<&mut MaybeUninit<Self>>,
+        mut init: impl FnMut(usize, Pin<&mut MaybeUninit<T>>) -> Result<(), E>,
+    ) -> Result<Pin<&mut Self>, E> {
+        let this = unsafe { this.get_unchecked_mut() };
+        unsafe { Self::init_raw(this, |i, e| init(i, Pin::new_unchecked(e))) }?;
+        Ok(unsafe { Pin::new_unchecked(this.assume_init_mut()) })
+    }
+
+    /// Create a new `PinArray` by calling `f` with the index of each element
+    ///
+    /// The array is built on the stack and returned by value, so it may be copied at least once
+    /// on the way out. For arrays too large for that use `PinArray::boxed_from_fn` (with the
+    /// `alloc` feature) to build it on the heap, or [`PinArray::init_pinned`] to initialise
+    /// memory you already have.
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// let p = PinArray::from_fn(|i| i * i);
+    /// assert_eq!(p.as_ref_array(), [&0, &1, &4, &9]);
+    /// ```
+    pub fn from_fn(mut f: impl FnMut(usize) -> T) -> Self {
+        match Self::try_from_fn(|i| Ok::<_, Infallible>(f(i))) {
+            Ok(p) => p,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Create a new `PinArray` by calling `f` with the index of each element, stopping at the
+    /// first error
+    ///
+    /// On error the elements which were already created are dropped in reverse order. Like
+    /// [`PinArray::from_fn`] the array is built on the stack and returned by value.
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// let p = PinArray::<u8, 3>::try_from_fn(|i| u8::try_from(i * 100));
+    /// assert!(p.is_ok());
+    /// let p = PinArray::<u8, 3>::try_from_fn(|i| u8::try_from(i * 200));
+    /// assert!(p.is_err());
+    /// ```
+    pub fn try_from_fn<E>(mut f: impl FnMut(usize) -> Result<T, E>) -> Result<Self, E> {
+        let mut out = MaybeUninit::<Self>::uninit();
+        unsafe {
+            Self::init_raw(&mut out, |i, e| {
+                e.write(f(i)?);
+                Ok(())
+            })
+        }?;
+        Ok(unsafe { out.assume_init() })
+    }
+
+    /// Create a new pinned `PinArray` on the heap by calling `f` with the index of each element
+    ///
+    /// The array is allocated uninitialised and each element is written straight into it, so
+    /// no temporary copy of the whole array is ever made on the stack. This requires the `alloc`
+    /// feature.
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// // 4MiB, which would overflow most stacks
+    /// let p = PinArray::<[u8; 4096], 1024>::boxed_from_fn(|i| [i as u8; 4096]);
+    /// assert_eq!(p.get(1023).map(|e| e[0]), Some(255));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn boxed_from_fn(mut f: impl FnMut(usize) -> T) -> Pin<Box<Self>> {
+        let mut out = Box::<Self>::new_uninit();
+        let res = unsafe {
+            Self::init_raw(&mut out, |i, e| {
+                e.write(f(i));
+                Ok::<_, Infallible>(())
+            })
+        };
+        match res {
+            Ok(()) => Box::into_pin(unsafe { out.assume_init() }),
+            Err(e) => match e {},
+        }
+    }
+
+    /// Create a new pinned `PinArray` of default values on the heap
+    ///
+    /// See [`PinArray::boxed_from_fn`]. This requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn boxed_default() -> Pin<Box<Self>>
+    where
+        T: Default,
+    {
+        Self::boxed_from_fn(|_| T::default())
+    }
+
+    /// Initialise every element of `this` in order with `init`, dropping the elements initialised
+    /// so far in reverse order if it fails or panics
+    ///
+    /// # Safety
+    /// `init` must fully initialise the element it is given if it returns `Ok`
+    unsafe fn init_raw<E>(
+        this: &mut MaybeUninit<Self>,
+        mut init: impl FnMut(usize, &mut MaybeUninit<T>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        // `PinArray` is `repr(transparent)` over `[T; SIZE]`, so the elements are laid out one
+        // after another from the start of `this`
+        let base = this.as_mut_ptr().cast::<MaybeUninit<T>>();
+        let mut guard = InitGuard {
+            base: base.cast::<T>(),
+            len: 0,
+        };
+        while guard.len < SIZE {
+            init(guard.len, unsafe { &mut *base.add(guard.len) })?;
+            guard.len += 1;
+        }
+        core::mem::forget(guard);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::{
+        cell::Cell,
+        marker::PhantomPinned,
+        mem::MaybeUninit,
+        pin::{pin, Pin},
+    };
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::PinArray;
+
+    /// Element which checks it is dropped where it was initialised and records the order
+    /// elements are dropped in
+    struct Tracked<'a> {
+        idx: usize,
+        addr: usize,
+        drops: &'a Cell<[usize; 4]>,
+        dropped: &'a Cell<usize>,
+        _p: PhantomPinned,
+    }
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            // elements created by value are moved into place so have no address to check
+            if self.addr != 0 {
+                assert_eq!(self.addr, core::ptr::from_ref(&*self).addr());
+            }
+            let n = self.dropped.get();
+            let mut drops = self.drops.get();
+            drops[n] = self.idx;
+            self.drops.set(drops);
+            self.dropped.set(n + 1);
+        }
+    }
+
+    fn init_tracked<'a>(
+        idx: usize,
+        slot: Pin<&mut MaybeUninit<Tracked<'a>>>,
+        drops: &'a Cell<[usize; 4]>,
+        dropped: &'a Cell<usize>,
+    ) {
+        let slot = unsafe { slot.get_unchecked_mut() };
+        let addr = core::ptr::from_ref(&*slot).addr();
+        slot.write(Tracked {
+            idx,
+            addr,
+            drops,
+            dropped,
+            _p: PhantomPinned,
+        });
+    }
+
+    #[test]
+    fn init_in_place() {
+        let drops = Cell::new([usize::MAX; 4]);
+        let dropped = Cell::new(0);
+        let mut slot = pin!(MaybeUninit::<PinArray<Tracked, 4>>::uninit());
+        let mut p = unsafe {
+            PinArray::init_pinned(slot.as_mut(), |i, e| init_tracked(i, e, &drops, &dropped))
+        };
+        assert_eq!(p.iter().map(|t| t.idx).sum::<usize>(), 6);
+        unsafe { core::ptr::drop_in_place(p.as_mut().get_unchecked_mut()) };
+        assert_eq!(drops.get(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn panic_drops_initialised() {
+        let drops = Cell::new([usize::MAX; 4]);
+        let dropped = Cell::new(0);
+        let mut slot = pin!(MaybeUninit::<PinArray<Tracked, 4>>::uninit());
+        let r = catch_unwind(AssertUnwindSafe(|| unsafe {
+            PinArray::init_pinned(slot.as_mut(), |i, e| {
+                if i == 3 {
+                    panic!("init failed");
+                }
+                init_tracked(i, e, &drops, &dropped);
+            });
+        }));
+        assert!(r.is_err());
+        assert_eq!(dropped.get(), 3);
+        assert_eq!(drops.get(), [2, 1, 0, usize::MAX]);
+    }
+
+    #[test]
+    fn error_rolls_back() {
+        let drops = Cell::new([usize::MAX; 4]);
+        let dropped = Cell::new(0);
+        let mut slot = pin!(MaybeUninit::<PinArray<Tracked, 4>>::uninit());
+        let res = unsafe {
+            PinArray::try_init_pinned(slot.as_mut(), |i, e| {
+                if i == 2 {
+                    return Err(i);
+                }
+                init_tracked(i, e, &drops, &dropped);
+                Ok(())
+            })
+        };
+        assert_eq!(res.err(), Some(2));
+        assert_eq!(dropped.get(), 2);
+        assert_eq!(drops.get(), [1, 0, usize::MAX, usize::MAX]);
+
+        // the slot can be reused after a failure
+        let mut p = unsafe {
+            PinArray::try_init_pinned(slot.as_mut(), |i, e| {
+                init_tracked(i, e, &drops, &dropped);
+                Ok::<_, ()>(())
+            })
+        }
+        .unwrap();
+        assert_eq!(dropped.get(), 2);
+        dropped.set(0);
+        unsafe { core::ptr::drop_in_place(p.as_mut().get_unchecked_mut()) };
+        assert_eq!(drops.get(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn from_fn() {
+        let p = PinArray::<_, 16>::from_fn(|i| [i; 64]);
+        assert!(p.iter().enumerate().all(|(i, e)| e.iter().all(|&v| v == i)));
+
+        let drops = Cell::new([usize::MAX; 4]);
+        let dropped = Cell::new(0);
+        let res = PinArray::<_, 4>::try_from_fn(|i| {
+            if i == 3 {
+                return Err("failed");
+            }
+            Ok(Tracked {
+                idx: i,
+                addr: 0,
+                drops: &drops,
+                dropped: &dropped,
+                _p: PhantomPinned,
+            })
+        });
+        assert!(res.is_err());
+        assert_eq!(drops.get(), [2, 1, 0, usize::MAX]);
+
+        static S: PinArray<u8, 3> = PinArray::new([1, 2, 3]);
+        assert_eq!(S.as_ref_array(), [&1, &2, &3]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn boxed() {
+        use alloc::boxed::Box;
+
+        let p = PinArray::<[u8; 4096], 1024>::boxed_from_fn(|i| [i as u8; 4096]);
+        assert!(p.iter().enumerate().all(|(i, e)| e[4095] == i as u8));
+
+        let p: Pin<Box<PinArray<Option<u32>, 3>>> = PinArray::boxed_default();
+        assert_eq!(p.as_ref_array(), [&None; 3]);
+
+        let drops = Cell::new([usize::MAX; 4]);
+        let dropped = Cell::new(0);
+        let p = PinArray::<_, 4>::boxed_from_fn(|i| Tracked {
+            idx: i,
+            addr: 0,
+            drops: &drops,
+            dropped: &dropped,
+            _p: PhantomPinned,
+        });
+        drop(p);
+        assert_eq!(drops.get(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut slot = pin!(MaybeUninit::<PinArray<(), 3>>::uninit());
+        let mut calls = 0;
+        let p = unsafe {
+            PinArray::init_pinned(slot.as_mut(), |_, e| {
+                e.get_unchecked_mut().write(());
+                calls += 1;
+            })
+        };
+        assert_eq!(p.len(), 3);
+        assert_eq!(calls, 3);
+
+        let mut empty = pin!(MaybeUninit::<PinArray<u8, 0>>::uninit());
+        let p = unsafe { PinArray::init_pinned(empty.as_mut(), |_, _| unreachable!()) };
+        assert!(p.is_empty());
+    }
+}
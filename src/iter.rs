@@ -1,28 +1,131 @@
-use core::{marker::PhantomData, pin::Pin, ptr::NonNull};
+use core::{iter::FusedIterator, marker::PhantomData, mem, pin::Pin, ptr::NonNull};
 
-use crate::PinArray;
+use crate::{PinArray, PinSlice};
+
+/// Start/end pointer pair shared by the array and slice iterators
+///
+/// This works the same way as `core::slice::Iter`: `ptr` is the next element from the front and
+/// `end` is one past the next element from the back. For zero-sized types the pointers never
+/// move, instead the address of `end` is used as a counter of the remaining elements.
+struct RawIter<T> {
+    ptr: NonNull<T>,
+    end: *const T,
+}
+
+impl<T> RawIter<T> {
+    const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+    /// # Safety
+    /// `ptr` must point to the start of `len` valid elements
+    unsafe fn new(ptr: NonNull<T>, len: usize) -> Self {
+        let end = if Self::IS_ZST {
+            ptr.as_ptr().wrapping_byte_add(len)
+        } else {
+            unsafe { ptr.as_ptr().add(len) }
+        };
+        Self { ptr, end }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        if Self::IS_ZST {
+            self.end.addr().wrapping_sub(self.ptr.as_ptr().addr())
+        } else {
+            unsafe { self.end.offset_from(self.ptr.as_ptr()) as usize }
+        }
+    }
+
+    /// Skip `n` elements from the front, saturating at the end
+    #[inline]
+    fn advance(&mut self, n: usize) {
+        let n = n.min(self.len());
+        if Self::IS_ZST {
+            self.end = self.end.wrapping_byte_sub(n);
+        } else {
+            self.ptr = unsafe { self.ptr.add(n) };
+        }
+    }
+
+    /// Skip `n` elements from the back, saturating at the front
+    #[inline]
+    fn advance_back(&mut self, n: usize) {
+        let n = n.min(self.len());
+        if Self::IS_ZST {
+            self.end = self.end.wrapping_byte_sub(n);
+        } else {
+            self.end = unsafe { self.end.sub(n) };
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ptr.as_ptr().cast_const() == self.end
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<NonNull<T>> {
+        if self.is_empty() {
+            None
+        } else if Self::IS_ZST {
+            self.end = self.end.wrapping_byte_sub(1);
+            Some(self.ptr)
+        } else {
+            let old = self.ptr;
+            self.ptr = unsafe { self.ptr.add(1) };
+            Some(old)
+        }
+    }
+
+    #[inline]
+    fn next_back(&mut self) -> Option<NonNull<T>> {
+        if self.is_empty() {
+            None
+        } else if Self::IS_ZST {
+            self.end = self.end.wrapping_byte_sub(1);
+            Some(self.ptr)
+        } else {
+            self.end = unsafe { self.end.sub(1) };
+            Some(unsafe { NonNull::new_unchecked(self.end.cast_mut()) })
+        }
+    }
+}
 
 macro_rules! impl_iter {
-    ($name:ident <$l:lifetime, $t:ident, $sz:ident> { type Item = $item:ty; idx = $i:ident; $me:ident => $get:expr }) => {
-        impl<$l, $t, const $sz: usize> ExactSizeIterator for $name<$l, $t, $sz> {}
-        impl<$l, $t, const $sz: usize> Iterator for $name<$l, $t, $sz> {
+    ($name:ident <$l:lifetime, $t:ident $(, const $sz:ident)?> { type Item = $item:ty; $p:ident => $get:expr }) => {
+        impl<$l, $t $(, const $sz: usize)?> ExactSizeIterator for $name<$l, $t $(, $sz)?> {}
+        impl<$l, $t $(, const $sz: usize)?> FusedIterator for $name<$l, $t $(, $sz)?> {}
+        impl<$l, $t $(, const $sz: usize)?> Iterator for $name<$l, $t $(, $sz)?> {
             type Item = $item;
 
+            #[inline]
             fn next(&mut self) -> Option<Self::Item> {
-                if self.$i >= $sz {
-                    None
-                } else {
-                    let $me = &self;
-                    let item = $get;
-                    self.i += 1;
-                    Some(item)
-                }
+                self.raw.next().map(|$p| $get)
             }
             fn size_hint(&self) -> (usize, Option<usize>) {
-                debug_assert!(self.$i <= $sz);
-                let sz = $sz - self.$i;
+                let sz = self.raw.len();
                 (sz, Some(sz))
             }
+            #[inline]
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                self.raw.advance(n);
+                self.next()
+            }
+            fn count(self) -> usize {
+                self.len()
+            }
+            fn last(mut self) -> Option<Self::Item> {
+                self.next_back()
+            }
+        }
+        impl<$l, $t $(, const $sz: usize)?> DoubleEndedIterator for $name<$l, $t $(, $sz)?> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.raw.next_back().map(|$p| $get)
+            }
+            #[inline]
+            fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+                self.raw.advance_back(n);
+                self.next_back()
+            }
         }
     };
 }
@@ -31,13 +134,41 @@ macro_rules! impl_iter {
 ///
 /// For more see [`PinArray::iter`]
 pub struct Iter<'p, T, const SZ: usize> {
-    pub(crate) i: usize,
-    pub(crate) els: &'p PinArray<T, SZ>,
+    raw: RawIter<T>,
+    _phant: PhantomData<&'p PinArray<T, SZ>>,
 }
 
+unsafe impl<T: Sync, const SZ: usize> Send for Iter<'_, T, SZ> {}
+unsafe impl<T: Sync, const SZ: usize> Sync for Iter<'_, T, SZ> {}
+
 impl<'p, T, const SZ: usize> Iter<'p, T, SZ> {
+    /// Create from a reference to its target
     pub fn new(els: &'p PinArray<T, SZ>) -> Self {
-        Self { i: 0, els }
+        Self {
+            raw: unsafe { RawIter::new(NonNull::from(&els.elements).cast(), SZ) },
+            _phant: PhantomData,
+        }
+    }
+}
+
+/// Iterator over pinned shared references of a [`PinArray`]
+///
+/// For more see [`PinArray::iter_pin_ref`]
+pub struct IterPinRef<'p, T, const SZ: usize> {
+    raw: RawIter<T>,
+    _phant: PhantomData<&'p PinArray<T, SZ>>,
+}
+
+unsafe impl<T: Sync, const SZ: usize> Send for IterPinRef<'_, T, SZ> {}
+unsafe impl<T: Sync, const SZ: usize> Sync for IterPinRef<'_, T, SZ> {}
+
+impl<'p, T, const SZ: usize> IterPinRef<'p, T, SZ> {
+    /// Create from a pinned reference to its target
+    pub fn new(els: Pin<&'p PinArray<T, SZ>>) -> Self {
+        Self {
+            raw: unsafe { RawIter::new(NonNull::from(&els.get_ref().elements).cast(), SZ) },
+            _phant: PhantomData,
+        }
     }
 }
 
@@ -45,45 +176,182 @@ impl<'p, T, const SZ: usize> Iter<'p, T, SZ> {
 ///
 /// For more see [`PinArray::iter_mut`]
 pub struct IterMut<'p, T, const SZ: usize> {
-    i: usize,
-    el_ptr: NonNull<T>,
+    raw: RawIter<T>,
     _phant: PhantomData<&'p mut PinArray<T, SZ>>,
 }
 
+unsafe impl<T: Send, const SZ: usize> Send for IterMut<'_, T, SZ> {}
+unsafe impl<T: Sync, const SZ: usize> Sync for IterMut<'_, T, SZ> {}
+
 impl<'p, T, const SZ: usize> IterMut<'p, T, SZ> {
     /// Create from a mutable reference to its target
     ///
     /// Note that without unsafe code this is not possible to call directly unless `T` is [`Unpin`]
     /// you should use [`PinArray::iter_mut`] instead
-    pub fn new(parent: &mut PinArray<T, SZ>) -> Self {
+    pub fn new(parent: &'p mut PinArray<T, SZ>) -> Self {
         Self {
-            i: 0,
-            el_ptr: unsafe { NonNull::new_unchecked(parent.elements.as_mut_ptr()) },
+            raw: unsafe { RawIter::new(NonNull::from(&mut parent.elements).cast(), SZ) },
             _phant: PhantomData,
         }
     }
 }
 
-impl_iter!(Iter <'p, T, SZ> {
-    type Item = &'p T;
-    idx = i;
-    me => me.els.get(me.i).unwrap()
+/// Iterator over references of a [`PinSlice`]
+///
+/// For more see [`PinSlice::iter`]
+pub struct SliceIter<'p, T> {
+    raw: RawIter<T>,
+    _phant: PhantomData<&'p PinSlice<T>>,
+}
+
+unsafe impl<T: Sync> Send for SliceIter<'_, T> {}
+unsafe impl<T: Sync> Sync for SliceIter<'_, T> {}
 
+impl<'p, T> SliceIter<'p, T> {
+    /// Create from a reference to its target
+    pub fn new(els: &'p PinSlice<T>) -> Self {
+        Self {
+            raw: unsafe { RawIter::new(NonNull::from(els).cast(), els.len()) },
+            _phant: PhantomData,
+        }
+    }
+}
+
+/// Iterator over pinned shared references of a [`PinSlice`]
+///
+/// For more see [`PinSlice::iter_pin_ref`]
+pub struct SliceIterPinRef<'p, T> {
+    raw: RawIter<T>,
+    _phant: PhantomData<&'p PinSlice<T>>,
+}
+
+unsafe impl<T: Sync> Send for SliceIterPinRef<'_, T> {}
+unsafe impl<T: Sync> Sync for SliceIterPinRef<'_, T> {}
+
+impl<'p, T> SliceIterPinRef<'p, T> {
+    /// Create from a pinned reference to its target
+    pub fn new(els: Pin<&'p PinSlice<T>>) -> Self {
+        let els = els.get_ref();
+        Self {
+            raw: unsafe { RawIter::new(NonNull::from(els).cast(), els.len()) },
+            _phant: PhantomData,
+        }
+    }
+}
+
+/// Iterator over pinned parts of a [`PinSlice`]
+///
+/// For more see [`PinSlice::iter_mut`]
+pub struct SliceIterMut<'p, T> {
+    raw: RawIter<T>,
+    _phant: PhantomData<&'p mut PinSlice<T>>,
+}
+
+unsafe impl<T: Send> Send for SliceIterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for SliceIterMut<'_, T> {}
+
+impl<'p, T> SliceIterMut<'p, T> {
+    /// Create from a mutable reference to its target
+    ///
+    /// Note that without unsafe code this is not possible to call directly unless `T` is [`Unpin`]
+    /// you should use [`PinSlice::iter_mut`] instead
+    pub fn new(parent: &'p mut PinSlice<T>) -> Self {
+        let len = parent.len();
+        Self {
+            raw: unsafe { RawIter::new(NonNull::from(parent).cast(), len) },
+            _phant: PhantomData,
+        }
+    }
+}
+
+impl_iter!(Iter <'p, T, const SZ> {
+    type Item = &'p T;
+    p => unsafe { p.as_ref() }
 });
-impl_iter!(IterMut <'p, T, SZ> {
+impl_iter!(IterPinRef <'p, T, const SZ> {
+    type Item = Pin<&'p T>;
+    p => unsafe { Pin::new_unchecked(p.as_ref()) }
+});
+impl_iter!(IterMut <'p, T, const SZ> {
     type Item = Pin<&'p mut T>;
-    idx = i;
-    me => unsafe {
-        Pin::new_unchecked(me.el_ptr.as_ptr().add(me.i).as_mut().unwrap())
+    p => unsafe { Pin::new_unchecked(&mut *p.as_ptr()) }
+});
+impl_iter!(SliceIter <'p, T> {
+    type Item = &'p T;
+    p => unsafe { p.as_ref() }
+});
+impl_iter!(SliceIterPinRef <'p, T> {
+    type Item = Pin<&'p T>;
+    p => unsafe { Pin::new_unchecked(p.as_ref()) }
+});
+impl_iter!(SliceIterMut <'p, T> {
+    type Item = Pin<&'p mut T>;
+    p => unsafe { Pin::new_unchecked(&mut *p.as_ptr()) }
+});
+
+/// Owning iterator over the elements of a [`PinArray`]
+///
+/// For more see [`PinArray::into_iter_unchecked`]
+#[derive(Clone, Debug)]
+pub struct IntoIter<T, const SZ: usize> {
+    inner: core::array::IntoIter<T, SZ>,
+}
+
+impl<T, const SZ: usize> IntoIter<T, SZ> {
+    /// Create from an owned array, see `PinArray::into_iter` and [`PinArray::into_iter_unchecked`]
+    pub fn new(parent: PinArray<T, SZ>) -> Self
+    where
+        T: Unpin,
+    {
+        Self {
+            inner: parent.into_inner().into_iter(),
+        }
     }
 
-});
+    pub(crate) fn from_array(elements: [T; SZ]) -> Self {
+        Self {
+            inner: elements.into_iter(),
+        }
+    }
+}
+
+impl<T, const SZ: usize> Iterator for IntoIter<T, SZ> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n)
+    }
+    fn count(self) -> usize {
+        self.inner.count()
+    }
+    fn last(self) -> Option<Self::Item> {
+        self.inner.last()
+    }
+}
+impl<T, const SZ: usize> DoubleEndedIterator for IntoIter<T, SZ> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth_back(n)
+    }
+}
+impl<T, const SZ: usize> ExactSizeIterator for IntoIter<T, SZ> {}
+impl<T, const SZ: usize> FusedIterator for IntoIter<T, SZ> {}
 
 #[cfg(test)]
 mod tests {
     use crate::PinArray;
 
-    use super::Iter;
+    use core::pin::pin;
+
+    use super::{Iter, IterMut};
     #[test]
     fn size_matches() {
         let pa = PinArray::new([1, 2, 3]);
@@ -96,4 +364,122 @@ mod tests {
         i.next();
         assert_eq!(i.len(), 0);
     }
+
+    #[test]
+    fn double_ended() {
+        let pa = PinArray::new([1, 2, 3, 4]);
+        let mut i = pa.iter();
+        assert_eq!(i.next_back(), Some(&4));
+        assert_eq!(i.next(), Some(&1));
+        assert_eq!(i.len(), 2);
+        assert_eq!(i.next_back(), Some(&3));
+        assert_eq!(i.next_back(), Some(&2));
+        assert_eq!(i.next_back(), None);
+        assert_eq!(i.next(), None);
+    }
+
+    #[test]
+    fn nth_and_nth_back() {
+        let pa = PinArray::new([0, 1, 2, 3, 4, 5]);
+        let mut i = pa.iter();
+        assert_eq!(i.nth(1), Some(&1));
+        assert_eq!(i.nth_back(1), Some(&4));
+        assert_eq!(i.len(), 2);
+        assert_eq!(i.nth(5), None);
+        assert_eq!(i.len(), 0);
+        assert_eq!(i.next_back(), None);
+
+        let mut i = pa.iter();
+        assert_eq!(i.nth_back(usize::MAX), None);
+        assert_eq!(i.next(), None);
+    }
+
+    #[test]
+    fn adaptors_mut() {
+        let mut pa = pin!(PinArray::new([0, 1, 2, 3, 4, 5, 6]));
+        for mut e in pa.as_mut().iter_mut().rev().step_by(3) {
+            *e += 10;
+        }
+        assert_eq!(pa.as_ref_array(), [&10, &1, &2, &13, &4, &5, &16]);
+        assert_eq!(pa.as_mut().iter_mut().skip(2).count(), 5);
+        assert_eq!(pa.as_mut().iter_mut().last().map(|e| *e), Some(16));
+        assert_eq!(pa.iter().rev().skip(5).copied().next(), Some(1));
+    }
+
+    #[test]
+    fn fused_mut() {
+        let mut pa = pin!(PinArray::new([1]));
+        let mut i = IterMut::new(unsafe { pa.as_mut().get_unchecked_mut() });
+        assert!(i.next().is_some());
+        assert!(i.next().is_none());
+        assert!(i.next_back().is_none());
+        assert!(i.next().is_none());
+    }
+
+    #[test]
+    fn zst_double_ended() {
+        let mut pa = pin!(PinArray::new([(); 5]));
+        let mut i = pa.as_mut().iter_mut();
+        assert_eq!(i.len(), 5);
+        assert!(i.next_back().is_some());
+        assert!(i.nth(2).is_some());
+        assert_eq!(i.len(), 1);
+        assert!(i.next().is_some());
+        assert!(i.next_back().is_none());
+        assert_eq!(pa.iter().rev().skip(4).count(), 1);
+    }
+
+    #[test]
+    fn into_iter_drops_remaining() {
+        use core::cell::Cell;
+
+        struct D<'a>(&'a Cell<u32>);
+        impl Drop for D<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+        let drops = Cell::new(0);
+        let mut i = PinArray::new([D(&drops), D(&drops), D(&drops)]).into_iter();
+        drop(i.next_back());
+        assert_eq!(drops.get(), 1);
+        drop(i);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn slice_iters() {
+        let mut pa = pin!(PinArray::new([0, 1, 2, 3]));
+        let mut s = pa.as_mut().as_pin_slice();
+        for mut e in s.as_mut().iter_mut().skip(1) {
+            *e *= 10;
+        }
+        assert!(s.iter().rev().copied().eq([30, 20, 10, 0]));
+        assert_eq!(s.as_ref().iter_pin_ref().nth(2).map(|e| *e), Some(20));
+        assert_eq!(s.iter().len(), 4);
+    }
+
+    #[test]
+    fn empty() {
+        let mut pa = pin!(PinArray::<u32, 0>::new([]));
+        assert_eq!(pa.iter().next(), None);
+        assert_eq!(pa.as_mut().iter_mut().next_back(), None);
+    }
+}
+
+#[cfg(test)]
+mod impl_tests {
+    use super::*;
+    use core::cell::Cell;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    assert_impl_all!(Iter<'static, u32, 1>: Send, Sync);
+    assert_impl_all!(IterMut<'static, u32, 1>: Send, Sync);
+    assert_impl_all!(IterMut<'static, Cell<u32>, 1>: Send);
+    assert_not_impl_any!(Iter<'static, Cell<u32>, 1>: Send, Sync);
+    assert_not_impl_any!(IterMut<'static, Cell<u32>, 1>: Sync);
+    assert_impl_all!(SliceIter<'static, u32>: Send, Sync);
+    assert_impl_all!(SliceIterMut<'static, Cell<u32>>: Send);
+    assert_not_impl_any!(SliceIter<'static, Cell<u32>>: Send, Sync);
+    assert_not_impl_any!(SliceIterMut<'static, Cell<u32>>: Sync);
 }
@@ -3,35 +3,65 @@
 //!
 //! [structurally projecting]: https://doc.rust-lang.org/std/pin/index.html#projections-and-structural-pinning
 //!
-//! This crate is `no_std` compatible and does not require `alloc`.
+//! This crate is `no_std` compatible and does not require `alloc` unless the `alloc` feature is
+//! enabled.
 #![no_std]
 
-use core::{marker::PhantomPinned, pin::Pin};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use iter::{Iter, IterMut};
+use core::{marker::PhantomPinned, ops::RangeBounds, pin::Pin};
 
+use iter::{IntoIter, Iter, IterMut, IterPinRef};
+
+mod bits;
+pub mod executor;
+pub mod future;
+mod init;
 pub mod iter;
+pub mod maybe;
+mod rotate;
+mod set;
+#[cfg(feature = "futures-sink")]
+pub mod sink;
+mod slice;
+#[cfg(feature = "futures-core")]
+pub mod stream;
+#[cfg(feature = "alloc")]
+pub mod vec;
+
+pub use maybe::PinArrayMaybe;
+pub use set::PinFutureSet;
+pub use slice::{GetManyPinError, PinSlice};
+#[cfg(feature = "alloc")]
+pub use vec::PinVec;
 
 /// A [structurally pinned][structural pinning] array of values
 ///
 /// [structural pinning]: https://doc.rust-lang.org/std/pin/index.html#projections-and-structural-pinning
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+#[repr(transparent)]
 pub struct PinArray<T, const SIZE: usize> {
     elements: [T; SIZE],
     _pin: PhantomPinned,
 }
 impl<T: Default, const SIZE: usize> Default for PinArray<T, SIZE> {
     fn default() -> Self {
-        Self {
-            elements: core::array::from_fn(|_| Default::default()),
-            _pin: Default::default(),
-        }
+        Self::from_fn(|_| Default::default())
     }
 }
 
 impl<T, const SIZE: usize> PinArray<T, SIZE> {
     /// Create a new `PinArray` from elements
-    pub fn new(elements: [T; SIZE]) -> Self {
+    ///
+    /// This is a `const fn` so can be used to initialise `static` and `const` items
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// static P: PinArray<u8, 3> = PinArray::new([1, 2, 3]);
+    /// assert_eq!(P.len(), 3);
+    /// ```
+    pub const fn new(elements: [T; SIZE]) -> Self {
         Self {
             elements,
             _pin: PhantomPinned,
@@ -74,6 +104,22 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
         self.elements.get(idx)
     }
 
+    /// Attempt to get a pinned shared reference to an element by index
+    ///
+    /// Unlike [`PinArray::get`] this keeps the pinning guarantee, which is needed to call
+    /// `self: Pin<&Self>` methods on the element
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new([1, 2, 3]));
+    /// assert_eq!(p.as_ref().get_pin_ref(0), Some(Pin::new(&1)));
+    /// assert_eq!(p.as_ref().get_pin_ref(3), None);
+    /// ```
+    pub fn get_pin_ref(self: Pin<&Self>, idx: usize) -> Option<Pin<&T>> {
+        self.as_pin_ref_slice().get_pin_ref(idx)
+    }
+
     /// Attempt to get a pinned reference to an element by index
     ///
     /// Note this requires `self` to be pinned
@@ -93,6 +139,186 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
             .map(|e| unsafe { Pin::new_unchecked(e) })
     }
 
+    /// Replace the element at `idx` with `value`, dropping the old element in place
+    ///
+    /// Gives `value` back if `idx` is out of bounds, see [`PinSlice::set_pin`]
+    ///
+    /// ```
+    /// # use core::{future::{pending, Future}, pin::{pin, Pin}};
+    /// # use pin_array::PinArray;
+    /// let mut tasks = pin!(PinArray::<Pin<Box<dyn Future<Output = ()>>>, 2>::new([
+    ///     Box::pin(pending()),
+    ///     Box::pin(pending()),
+    /// ]));
+    /// assert!(tasks.as_mut().set_pin(0, Box::pin(async {})).is_ok());
+    /// ```
+    pub fn set_pin(self: Pin<&mut Self>, idx: usize, value: T) -> Result<(), T> {
+        self.as_pin_slice().set_pin(idx, value)
+    }
+
+    /// Replace the element at `idx` with its default value, dropping the old element in place
+    ///
+    /// Returns `false` if `idx` is out of bounds, see [`PinSlice::reset_pin`]
+    ///
+    /// ```
+    /// # use core::pin::pin;
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// assert!(p.as_mut().reset_pin(0));
+    /// assert_eq!(p.as_ref_array(), [&0, &2, &3]);
+    /// ```
+    pub fn reset_pin(self: Pin<&mut Self>, idx: usize) -> bool
+    where
+        T: Default,
+    {
+        self.as_pin_slice().reset_pin(idx)
+    }
+
+    /// Get pinned references to several distinct elements at once
+    ///
+    /// Fails if any index is out of bounds or if any two indices are the same, see
+    /// [`PinSlice::get_many_pin`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let [mut a, b] = p.as_mut().get_many_pin([0, 2]).unwrap();
+    /// *a += *b;
+    /// assert_eq!(p.get(0), Some(&4));
+    /// assert!(p.as_mut().get_many_pin([1, 1]).is_err());
+    /// assert!(p.as_mut().get_many_pin([3]).is_err());
+    /// ```
+    pub fn get_many_pin<const K: usize>(
+        self: Pin<&mut Self>,
+        indices: [usize; K],
+    ) -> Result<[Pin<&mut T>; K], GetManyPinError> {
+        self.as_pin_slice().get_many_pin(indices)
+    }
+
+    /// Get pinned references to several elements at once without checking the indices
+    ///
+    /// # Safety
+    /// Every index must be less than `SIZE` and no two indices may be the same. See
+    /// [`PinArray::get_many_pin`] for a checked version
+    pub unsafe fn get_many_pin_unchecked<const K: usize>(
+        self: Pin<&mut Self>,
+        indices: [usize; K],
+    ) -> [Pin<&mut T>; K] {
+        unsafe { self.as_pin_slice().get_many_pin_unchecked(indices) }
+    }
+
+    /// Attempt to get a pinned [`PinSlice`] over a sub-range of the elements
+    ///
+    /// Returns `None` if the range is out of bounds, see [`PinSlice::get_pin_range`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3, 4, 5]));
+    /// let mut front = p.as_mut().get_pin_range(..4).unwrap();
+    /// assert_eq!(front.as_mut().get_pin(3), Some(Pin::new(&mut 4)));
+    /// assert!(p.as_mut().get_pin_range(4..=5).is_none());
+    /// ```
+    pub fn get_pin_range<R: RangeBounds<usize>>(
+        self: Pin<&mut Self>,
+        range: R,
+    ) -> Option<Pin<&mut PinSlice<T>>> {
+        self.as_pin_slice().get_pin_range(range)
+    }
+
+    /// Project to a pinned `PinArray` over the constant window `[START, START + LEN)`
+    ///
+    /// The bounds are checked at compile time, so this cannot fail at runtime
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3, 4, 5]));
+    /// let sub: Pin<&mut PinArray<_, 2>> = p.as_mut().sub_pin::<1, 2>();
+    /// assert_eq!(sub.as_ref_array(), [&2, &3]);
+    /// ```
+    ///
+    /// Windows which do not fit are rejected
+    ///
+    /// ```compile_fail
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// p.as_mut().sub_pin::<2, 2>();
+    /// ```
+    pub fn sub_pin<const START: usize, const LEN: usize>(
+        self: Pin<&mut Self>,
+    ) -> Pin<&mut PinArray<T, LEN>> {
+        const {
+            assert!(
+                START <= SIZE && LEN <= SIZE - START,
+                "sub_pin window out of bounds"
+            )
+        };
+        unsafe {
+            self.map_unchecked_mut(|s| {
+                // SAFETY: the window is in bounds (checked above) and `PinArray` is
+                // `repr(transparent)` over `[T; LEN]`
+                &mut *(s.elements.as_mut_ptr().add(START) as *mut PinArray<T, LEN>)
+            })
+        }
+    }
+
+    /// Split this pinned `PinArray` into two disjoint pinned slices at `mid`
+    ///
+    /// See [`PinSlice::split_at_pin`]
+    ///
+    /// # Panics
+    /// If `mid > SIZE`
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3, 4]));
+    /// let (mut l, mut r) = p.as_mut().split_at_pin(2);
+    /// assert_eq!(l.as_mut().get_pin(0), Some(Pin::new(&mut 1)));
+    /// assert_eq!(r.as_mut().get_pin(0), Some(Pin::new(&mut 3)));
+    /// ```
+    pub fn split_at_pin(
+        self: Pin<&mut Self>,
+        mid: usize,
+    ) -> (Pin<&mut PinSlice<T>>, Pin<&mut PinSlice<T>>) {
+        self.as_pin_slice().split_at_pin(mid)
+    }
+
+    /// Split off the first element, returning `None` if the array is empty
+    ///
+    /// See [`PinSlice::split_first_pin`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let (first, rest) = p.as_mut().split_first_pin().unwrap();
+    /// assert_eq!(first, Pin::new(&mut 1));
+    /// assert_eq!(rest.len(), 2);
+    /// ```
+    pub fn split_first_pin(self: Pin<&mut Self>) -> Option<(Pin<&mut T>, Pin<&mut PinSlice<T>>)> {
+        self.as_pin_slice().split_first_pin()
+    }
+
+    /// Split off the last element, returning `None` if the array is empty
+    ///
+    /// See [`PinSlice::split_last_pin`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let (last, rest) = p.as_mut().split_last_pin().unwrap();
+    /// assert_eq!(last, Pin::new(&mut 3));
+    /// assert_eq!(rest.len(), 2);
+    /// ```
+    pub fn split_last_pin(self: Pin<&mut Self>) -> Option<(Pin<&mut T>, Pin<&mut PinSlice<T>>)> {
+        self.as_pin_slice().split_last_pin()
+    }
+
     /// Convert this `PinArray` to an array of references
     ///
     /// Immutable counterpart to [`PinArray::as_pin_array`]
@@ -107,6 +333,21 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
         core::array::from_fn(|i| &self.elements[i])
     }
 
+    /// Convert this pinned `PinArray` to an array of pinned shared references
+    ///
+    /// Pinned counterpart to [`PinArray::as_ref_array`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new(["a", "b"]));
+    /// assert_eq!(p.as_ref().as_pin_ref_array(), [Pin::new(&"a"), Pin::new(&"b")]);
+    /// ```
+    pub fn as_pin_ref_array(self: Pin<&Self>) -> [Pin<&T>; SIZE] {
+        let els = &self.get_ref().elements;
+        core::array::from_fn(|i| unsafe { Pin::new_unchecked(&els[i]) })
+    }
+
     /// Convert this pinned `PinArray` to an array of pinned mutable references
     ///
     /// Mutable counterpart to [`PinArray::as_ref_array`]
@@ -117,7 +358,7 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
     /// let mut p = pin!(PinArray::new(["a", "b"]));
     /// assert_eq!(p.as_pin_array(), [Pin::new(&mut "a"), Pin::new(&mut "b")]);
     /// ```
-    pub fn as_pin_array<'me>(self: Pin<&'me mut Self>) -> [Pin<&'me mut T>; SIZE] {
+    pub fn as_pin_array(self: Pin<&mut Self>) -> [Pin<&mut T>; SIZE] {
         let arr = unsafe { self.get_unchecked_mut().elements.as_mut_ptr() };
         core::array::from_fn(|i| {
             let p = unsafe { arr.add(i) };
@@ -125,6 +366,49 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
         })
     }
 
+    /// View this `PinArray` as a [`PinSlice`]
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// let p = PinArray::new([1, 2, 3]);
+    /// assert_eq!(p.as_slice().len(), 3);
+    /// ```
+    pub fn as_slice(&self) -> &PinSlice<T> {
+        PinSlice::from_ref(&self.elements)
+    }
+
+    /// Convert this pinned shared `PinArray` to a pinned shared [`PinSlice`]
+    ///
+    /// Shared counterpart to [`PinArray::as_pin_slice`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new([1, 2, 3]));
+    /// assert_eq!(p.as_ref().as_pin_ref_slice().len(), 3);
+    /// ```
+    pub fn as_pin_ref_slice(self: Pin<&Self>) -> Pin<&PinSlice<T>> {
+        unsafe { self.map_unchecked(|s| s.as_slice()) }
+    }
+
+    /// Convert this pinned `PinArray` to a pinned [`PinSlice`]
+    ///
+    /// This erases the length from the type so that functions taking a `Pin<&mut PinSlice<T>>`
+    /// can accept pinned arrays of any size
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::{PinArray, PinSlice};
+    /// fn first(s: Pin<&mut PinSlice<u32>>) -> Option<Pin<&mut u32>> {
+    ///     s.get_pin(0)
+    /// }
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// assert_eq!(first(p.as_mut().as_pin_slice()), Some(Pin::new(&mut 1)));
+    /// ```
+    pub fn as_pin_slice(self: Pin<&mut Self>) -> Pin<&mut PinSlice<T>> {
+        unsafe { self.map_unchecked_mut(|s| PinSlice::from_mut(&mut s.elements)) }
+    }
+
     /// Get an iterator over references to the elements
     ///
     /// ```
@@ -137,7 +421,22 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
     /// assert_eq!(i.next(), None);
     /// ```
     pub fn iter(&self) -> Iter<'_, T, SIZE> {
-        Iter { i: 0, els: self }
+        Iter::new(self)
+    }
+
+    /// Get an iterator over pinned shared references to the elements
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new(['h', 'i']));
+    /// let mut i = p.as_ref().iter_pin_ref();
+    /// assert_eq!(i.next(), Some(Pin::new(&'h')));
+    /// assert_eq!(i.next(), Some(Pin::new(&'i')));
+    /// assert_eq!(i.next(), None);
+    /// ```
+    pub fn iter_pin_ref(self: Pin<&Self>) -> IterPinRef<'_, T, SIZE> {
+        IterPinRef::new(self)
     }
 
     /// Get an iterator over pinned mutable references to the elements
@@ -155,20 +454,154 @@ impl<T, const SIZE: usize> PinArray<T, SIZE> {
     pub fn iter_mut(self: Pin<&mut Self>) -> IterMut<'_, T, SIZE> {
         IterMut::new(unsafe { self.get_unchecked_mut() })
     }
+
+    /// Consume this `PinArray` and get back the elements
+    ///
+    /// This requires `T: Unpin` since otherwise the elements may have been pinned, see
+    /// [`PinArray::into_inner_unchecked`] for a version without this bound
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// let p = PinArray::new([1, 2, 3]);
+    /// assert_eq!(p.into_inner(), [1, 2, 3]);
+    /// ```
+    pub fn into_inner(self) -> [T; SIZE]
+    where
+        T: Unpin,
+    {
+        self.elements
+    }
+
+    /// Consume this `PinArray` and get back the elements without requiring `T: Unpin`
+    ///
+    /// # Safety
+    /// The array must never have been pinned, as the elements are moved out
+    pub unsafe fn into_inner_unchecked(self) -> [T; SIZE] {
+        self.elements
+    }
+
+    /// Get an owning iterator over the elements without requiring `T: Unpin`
+    ///
+    /// This is the same as the by-value [`IntoIterator`] implementation for arrays of `Unpin` elements
+    ///
+    /// # Safety
+    /// The array must never have been pinned, as the elements are moved out
+    ///
+    /// ```
+    /// # use core::marker::PhantomPinned;
+    /// # use pin_array::PinArray;
+    /// let p = PinArray::new([(1, PhantomPinned), (2, PhantomPinned)]);
+    /// let mut i = unsafe { p.into_iter_unchecked() };
+    /// assert_eq!(i.next().map(|e| e.0), Some(1));
+    /// ```
+    pub unsafe fn into_iter_unchecked(self) -> IntoIter<T, SIZE> {
+        IntoIter::from_array(unsafe { self.into_inner_unchecked() })
+    }
 }
 
 impl<T: Unpin, const SIZE: usize> Unpin for PinArray<T, SIZE> {}
 
+impl<T: Unpin, const SIZE: usize> IntoIterator for PinArray<T, SIZE> {
+    type Item = T;
+    type IntoIter = IntoIter<T, SIZE>;
+
+    /// ```
+    /// # use pin_array::PinArray;
+    /// let p = PinArray::new(['a', 'b']);
+    /// let mut i = p.into_iter();
+    /// assert_eq!(i.next(), Some('a'));
+    /// assert_eq!(i.next(), Some('b'));
+    /// assert_eq!(i.next(), None);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl<'a, T, const SIZE: usize> IntoIterator for &'a PinArray<T, SIZE> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, SIZE>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const SIZE: usize> IntoIterator for Pin<&'a PinArray<T, SIZE>> {
+    type Item = Pin<&'a T>;
+    type IntoIter = IterPinRef<'a, T, SIZE>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_pin_ref()
+    }
+}
+
+impl<'a, T, const SIZE: usize> IntoIterator for Pin<&'a mut PinArray<T, SIZE>> {
+    type Item = Pin<&'a mut T>;
+    type IntoIter = IterMut<'a, T, SIZE>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T, const SIZE: usize> From<&'a PinArray<T, SIZE>> for &'a PinSlice<T> {
+    fn from(value: &'a PinArray<T, SIZE>) -> Self {
+        value.as_slice()
+    }
+}
+
+impl<'a, T, const SIZE: usize> From<&'a mut PinArray<T, SIZE>> for &'a mut PinSlice<T> {
+    fn from(value: &'a mut PinArray<T, SIZE>) -> Self {
+        PinSlice::from_mut(&mut value.elements)
+    }
+}
+
+impl<'a, T, const SIZE: usize> From<Pin<&'a mut PinArray<T, SIZE>>> for Pin<&'a mut PinSlice<T>> {
+    fn from(value: Pin<&'a mut PinArray<T, SIZE>>) -> Self {
+        value.as_pin_slice()
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    extern crate std;
+
     use core::{
+        cell::Cell,
         marker::{PhantomData, PhantomPinned},
         ops::Deref,
         pin::{pin, Pin},
     };
+    use std::panic::{catch_unwind, AssertUnwindSafe};
 
     use crate::PinArray;
 
+    /// Element which counts how many times it is dropped, and panics on drop if `panic` is set
+    pub(crate) struct CountDrop<'a> {
+        drops: &'a Cell<usize>,
+        pub(crate) id: usize,
+        panic: bool,
+    }
+
+    impl<'a> CountDrop<'a> {
+        pub(crate) fn new(drops: &'a Cell<usize>, id: usize, panic: bool) -> Self {
+            Self { drops, id, panic }
+        }
+    }
+
+    impl Drop for CountDrop<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+            assert!(!self.panic);
+        }
+    }
+
+    /// Run `f`, returning whether it panicked
+    pub(crate) fn panics<R>(f: impl FnOnce() -> R) -> bool {
+        catch_unwind(AssertUnwindSafe(f)).is_err()
+    }
+
     #[derive(Clone, Copy, Debug, Default, Eq)]
     struct NotUnpin {
         _p: PhantomPinned,
@@ -223,6 +656,69 @@ mod tests {
         assert_ne!(v1, v2);
         // println!("{vs:#?}");
     }
+
+    #[test]
+    fn into_iterator() {
+        let mut arr = pin!(PinArray::new([1, 2, 3]));
+        for mut e in arr.as_mut() {
+            *e *= 2;
+        }
+        let mut sum = 0;
+        for e in &*arr {
+            sum += e;
+        }
+        assert_eq!(sum, 12);
+
+        let other = PinArray::new([10, 20, 30]);
+        for (mut a, b) in arr.as_mut().into_iter().zip(&other) {
+            *a += b;
+        }
+        assert_eq!(arr.as_ref_array(), [&12, &24, &36]);
+    }
+
+    #[test]
+    fn pin_ref_projection() {
+        struct Node {
+            v: u8,
+            _p: PhantomPinned,
+        }
+        impl Node {
+            fn value(self: Pin<&Self>) -> u8 {
+                self.v
+            }
+        }
+        let arr = pin!(PinArray::new(core::array::from_fn::<_, 3, _>(|i| Node {
+            v: i as u8,
+            _p: PhantomPinned,
+        })));
+        let arr = arr.as_ref();
+        assert_eq!(arr.get_pin_ref(2).map(Node::value), Some(2));
+        assert_eq!(arr.iter_pin_ref().rev().map(Node::value).next(), Some(2));
+        assert_eq!(arr.as_pin_ref_array().map(Node::value), [0, 1, 2]);
+        assert_eq!(arr.into_iter().map(Node::value).sum::<u8>(), 3);
+    }
+
+    #[test]
+    fn sub_pin_windows() {
+        let mut arr = pin!(PinArray::new([1, 2, 3, 4]));
+        assert_eq!(
+            arr.as_mut().sub_pin::<0, 4>().as_ref_array(),
+            [&1, &2, &3, &4]
+        );
+        assert_eq!(arr.as_mut().sub_pin::<4, 0>().len(), 0);
+        let mut tail = arr.as_mut().sub_pin::<2, 2>();
+        *tail.as_mut().get_pin(1).unwrap() = 10;
+        assert_eq!(arr.as_ref_array(), [&1, &2, &3, &10]);
+    }
+
+    #[test]
+    fn sub_pin_not_unpin() {
+        let mut arr = pin!(PinArray::new(core::array::from_fn::<_, 4, _>(|i| {
+            NotUnpin::new(i as u8)
+        })));
+        let sub = arr.as_mut().sub_pin::<1, 2>();
+        assert_eq!(sub.as_ref_array(), [&NotUnpin::new(1), &NotUnpin::new(2)]);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,416 @@
+//! A fixed capacity pinned array whose slots may be empty
+
+use core::{fmt, iter::FusedIterator, marker::PhantomPinned, mem::MaybeUninit, pin::Pin};
+
+use crate::bits::{self, WORD_BITS};
+
+/// A fixed capacity array of pinned slots which can each be empty or occupied
+///
+/// This replaces `PinArray<Option<T>, N>` for slots which are filled and emptied while pinned.
+/// Occupancy is tracked separately from the elements, so it does not add padding to every
+/// element the way `Option<T>` can.
+///
+/// Occupancy is stored as a bitset of `W` 32 bit words, which must be enough for `N` slots. `W`
+/// defaults to 1 which covers up to 32 slots, larger arrays need to specify it:
+///
+/// ```
+/// # use pin_array::PinArrayMaybe;
+/// let large = PinArrayMaybe::<u32, 100, 4>::new();
+/// ```
+///
+/// Elements are written directly into their slot and are never moved, removing an element drops
+/// it in place.
+///
+/// ```
+/// # use core::pin::pin;
+/// # use pin_array::PinArrayMaybe;
+/// let mut p = pin!(PinArrayMaybe::<u32, 4>::new());
+/// p.as_mut().insert_pin(1, 10).unwrap();
+/// p.as_mut().insert_pin(3, 30).unwrap();
+/// assert!(p.as_mut().insert_pin(1, 11).is_err());
+/// assert!(p.as_mut().remove(1));
+/// assert!(!p.is_occupied(1));
+/// assert!(p.iter().eq([(3, &30)]));
+/// ```
+pub struct PinArrayMaybe<T, const N: usize, const W: usize = 1> {
+    slots: [MaybeUninit<T>; N],
+    occupied: [u32; W],
+    len: usize,
+    _pin: PhantomPinned,
+}
+
+impl<T: Unpin, const N: usize, const W: usize> Unpin for PinArrayMaybe<T, N, W> {}
+
+impl<T, const N: usize, const W: usize> Default for PinArrayMaybe<T, N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, const W: usize> fmt::Debug for PinArrayMaybe<T, N, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize, const W: usize> Drop for PinArrayMaybe<T, N, W> {
+    fn drop(&mut self) {
+        self.clear_in_place();
+    }
+}
+
+/// Check if the bit for `idx` is set in `occupied`
+fn bit_set(occupied: &[u32], idx: usize) -> bool {
+    let (word, mask) = bits::locate(idx);
+    occupied[word] & mask != 0
+}
+
+impl<T, const N: usize, const W: usize> PinArrayMaybe<T, N, W> {
+    /// Create a new array with every slot empty
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N <= W * WORD_BITS,
+                "PinArrayMaybe needs W to be at least N / 32 rounded up"
+            )
+        };
+        Self {
+            slots: [const { MaybeUninit::uninit() }; N],
+            occupied: [0; W],
+            len: 0,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// Get the number of slots
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Get the number of occupied slots
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if every slot is empty
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if the slot at `idx` holds an element, this is `false` if `idx` is out of bounds
+    pub fn is_occupied(&self, idx: usize) -> bool {
+        idx < N && bit_set(&self.occupied, idx)
+    }
+
+    /// Set whether the slot at `idx` is occupied, returning whether it was before
+    fn set_occupied(&mut self, idx: usize, occupied: bool) -> bool {
+        let (word, mask) = bits::locate(idx);
+        let was = self.occupied[word] & mask != 0;
+        if occupied {
+            self.occupied[word] |= mask;
+        } else {
+            self.occupied[word] &= !mask;
+        }
+        was
+    }
+
+    /// Attempt to get a reference to the element in a slot
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        if !self.is_occupied(idx) {
+            return None;
+        }
+        Some(unsafe { self.slots[idx].assume_init_ref() })
+    }
+
+    /// Attempt to get a pinned reference to the element in a slot
+    pub fn get_pin(self: Pin<&mut Self>, idx: usize) -> Option<Pin<&mut T>> {
+        if !self.is_occupied(idx) {
+            return None;
+        }
+        let this = unsafe { self.get_unchecked_mut() };
+        Some(unsafe { Pin::new_unchecked(this.slots[idx].assume_init_mut()) })
+    }
+
+    /// Write `value` into an empty slot, returning a pinned reference to it
+    ///
+    /// Gives `value` back if the slot is occupied or `idx` is out of bounds
+    pub fn insert_pin(self: Pin<&mut Self>, idx: usize, value: T) -> Result<Pin<&mut T>, T> {
+        if idx >= N || self.is_occupied(idx) {
+            return Err(value);
+        }
+        let this = unsafe { self.get_unchecked_mut() };
+        this.set_occupied(idx, true);
+        this.len += 1;
+        Ok(unsafe { Pin::new_unchecked(this.slots[idx].write(value)) })
+    }
+
+    /// Drop the element in a slot in place, returning whether there was one
+    ///
+    /// The slot is empty afterwards even if dropping the element panics
+    pub fn remove(self: Pin<&mut Self>, idx: usize) -> bool {
+        if !self.is_occupied(idx) {
+            return false;
+        }
+        let this = unsafe { self.get_unchecked_mut() };
+        this.set_occupied(idx, false);
+        this.len -= 1;
+        unsafe { this.slots[idx].assume_init_drop() };
+        true
+    }
+
+    /// Drop every element in place, leaving all the slots empty
+    ///
+    /// If dropping an element panics the rest are still dropped
+    pub fn clear(self: Pin<&mut Self>) {
+        unsafe { self.get_unchecked_mut() }.clear_in_place();
+    }
+
+    fn clear_in_place(&mut self) {
+        /// Carries on clearing the remaining slots if dropping an element panics
+        struct Rest<'a, T, const N: usize, const W: usize>(&'a mut PinArrayMaybe<T, N, W>);
+        impl<T, const N: usize, const W: usize> Drop for Rest<'_, T, N, W> {
+            fn drop(&mut self) {
+                self.0.clear_in_place();
+            }
+        }
+
+        for i in 0..N {
+            if self.set_occupied(i, false) {
+                self.len -= 1;
+                let rest = Rest(self);
+                unsafe { rest.0.slots[i].assume_init_drop() };
+                core::mem::forget(rest);
+            }
+        }
+    }
+
+    /// Get an iterator over the occupied slots, yielding the index and a reference to each
+    /// element
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            slots: self.slots.iter(),
+            occupied: &self.occupied,
+            idx: 0,
+            remaining: self.len,
+        }
+    }
+
+    /// Get an iterator over the occupied slots, yielding the index and a pinned reference to each
+    /// element
+    pub fn iter_mut(self: Pin<&mut Self>) -> IterMut<'_, T> {
+        let this = unsafe { self.get_unchecked_mut() };
+        IterMut {
+            slots: this.slots.iter_mut(),
+            occupied: &this.occupied,
+            idx: 0,
+            remaining: this.len,
+        }
+    }
+}
+
+impl<'a, T, const N: usize, const W: usize> IntoIterator for &'a PinArrayMaybe<T, N, W> {
+    type Item = (usize, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize, const W: usize> IntoIterator for Pin<&'a mut PinArrayMaybe<T, N, W>> {
+    type Item = (usize, Pin<&'a mut T>);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over the occupied slots of a [`PinArrayMaybe`]
+pub struct Iter<'a, T> {
+    slots: core::slice::Iter<'a, MaybeUninit<T>>,
+    occupied: &'a [u32],
+    idx: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            let slot = self.slots.next()?;
+            let idx = self.idx;
+            self.idx += 1;
+            if bit_set(self.occupied, idx) {
+                self.remaining -= 1;
+                return Some((idx, unsafe { slot.assume_init_ref() }));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Iterator over pinned references to the occupied slots of a [`PinArrayMaybe`]
+pub struct IterMut<'a, T> {
+    slots: core::slice::IterMut<'a, MaybeUninit<T>>,
+    occupied: &'a [u32],
+    idx: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (usize, Pin<&'a mut T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            let slot = self.slots.next()?;
+            let idx = self.idx;
+            self.idx += 1;
+            if bit_set(self.occupied, idx) {
+                self.remaining -= 1;
+                return Some((idx, unsafe { Pin::new_unchecked(slot.assume_init_mut()) }));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        cell::Cell,
+        mem::size_of,
+        pin::{pin, Pin},
+    };
+
+    use crate::{
+        tests::{panics, CountDrop},
+        PinArray, PinArrayMaybe,
+    };
+
+    #[test]
+    fn insert_and_remove() {
+        let drops = Cell::new(0);
+        let mut p = pin!(PinArrayMaybe::<_, 3>::new());
+        for i in [0, 2] {
+            assert!(p
+                .as_mut()
+                .insert_pin(i, CountDrop::new(&drops, 0, false))
+                .is_ok());
+        }
+        assert_eq!(p.len(), 2);
+        assert!(p.is_occupied(2));
+        assert!(!p.is_occupied(1));
+        assert!(!p.is_occupied(3));
+        assert!(p.get(1).is_none());
+        assert!(p.as_mut().get_pin(2).is_some());
+
+        let rejected = p
+            .as_mut()
+            .insert_pin(3, CountDrop::new(&drops, 0, false))
+            .err();
+        drop(rejected);
+        assert_eq!(drops.get(), 1);
+
+        assert!(p.as_mut().remove(0));
+        assert!(!p.as_mut().remove(0));
+        assert_eq!(drops.get(), 2);
+        assert_eq!(p.len(), 1);
+    }
+
+    #[test]
+    fn iterate_occupied() {
+        let mut p = pin!(PinArrayMaybe::<u32, 5>::new());
+        for i in [1, 2, 4] {
+            let _ = p.as_mut().insert_pin(i, i as u32 * 10);
+        }
+        let mut it = p.iter();
+        assert_eq!(it.len(), 3);
+        assert_eq!(it.next(), Some((1, &10)));
+        assert!(it.eq([(2, &20), (4, &40)]));
+
+        for (_, mut e) in p.as_mut() {
+            *e += 1;
+        }
+        assert!(p.iter().map(|(_, e)| *e).eq([11, 21, 41]));
+    }
+
+    #[test]
+    fn drop_continues_after_panic() {
+        let drops = Cell::new(0);
+        assert!(panics(|| {
+            let mut p = pin!(PinArrayMaybe::<_, 4>::new());
+            for i in 0..4 {
+                let _ = p.as_mut().insert_pin(i, CountDrop::new(&drops, i, i == 1));
+            }
+        }));
+        assert_eq!(drops.get(), 4);
+
+        let mut p = pin!(PinArrayMaybe::<_, 2>::new());
+        let _ = p.as_mut().insert_pin(0, CountDrop::new(&drops, 0, true));
+        assert!(panics(|| p.as_mut().remove(0)));
+        assert!(p.is_empty());
+        assert!(!p.is_occupied(0));
+    }
+
+    #[test]
+    fn many_words() {
+        let mut p = pin!(PinArrayMaybe::<usize, 70, 3>::new());
+        for i in [0, 31, 32, 33, 69] {
+            assert!(p.as_mut().insert_pin(i, i).is_ok());
+        }
+        assert!(p.as_mut().insert_pin(32, 0).is_err());
+        assert!(p.as_mut().insert_pin(70, 0).is_err());
+        assert!(p.is_occupied(33));
+        assert!(!p.is_occupied(34));
+        assert!(p.as_mut().remove(31));
+        assert!(p
+            .iter()
+            .map(|(i, e)| (i, *e))
+            .eq([0, 32, 33, 69].map(|i| (i, i))));
+        p.as_mut().clear();
+        assert!(p.is_empty());
+        assert!(!p.is_occupied(69));
+    }
+
+    #[test]
+    fn smaller_than_option() {
+        assert!(size_of::<PinArrayMaybe<u64, 8>>() < size_of::<PinArray<Option<u64>, 8>>());
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut p = pin!(PinArrayMaybe::<(), 3>::new());
+        let _: Pin<&mut ()> = p.as_mut().insert_pin(1, ()).unwrap();
+        assert!(p.iter().eq([(1, &())]));
+        p.as_mut().clear();
+        assert!(p.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod impl_tests {
+    use super::*;
+    use core::cell::Cell;
+    use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+    assert_impl_all!(PinArrayMaybe<u32, 1>: Unpin, Send, Sync);
+    assert_not_impl_all!(PinArrayMaybe<PhantomPinned, 1>: Unpin);
+    assert_not_impl_any!(PinArrayMaybe<Cell<u32>, 1>: Sync);
+    assert_impl_all!(PinArrayMaybe<u32, 64, 2>: Unpin, Send, Sync);
+}
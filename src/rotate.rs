@@ -0,0 +1,49 @@
+/// Index to start polling from, for combinators which poll several elements in turn
+///
+/// Starting from a different element each time means one which is always ready cannot starve
+/// the others, as it would if the elements were always polled in index order.
+#[derive(Default)]
+pub(crate) struct Rotation {
+    start: usize,
+}
+
+impl Rotation {
+    pub(crate) const fn new() -> Self {
+        Self { start: 0 }
+    }
+
+    /// Iterate over every index below `len` from the current start, without moving it
+    pub(crate) fn iter(&self, len: usize) -> impl Iterator<Item = usize> {
+        (self.start..len).chain(0..self.start)
+    }
+
+    /// Iterate over every index below `len` from the current start, moving the start along by one
+    /// for the next round
+    pub(crate) fn round(&mut self, len: usize) -> impl Iterator<Item = usize> {
+        let iter = self.iter(len);
+        self.start_after(self.start, len);
+        iter
+    }
+
+    /// Start the next round just after `idx`
+    pub(crate) fn start_after(&mut self, idx: usize, len: usize) {
+        self.start = if idx + 1 >= len { 0 } else { idx + 1 };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rotation;
+
+    #[test]
+    fn rotates() {
+        let mut r = Rotation::new();
+        assert!(r.round(3).eq([0, 1, 2]));
+        assert!(r.round(3).eq([1, 2, 0]));
+        assert!(r.iter(3).eq([2, 0, 1]));
+        r.start_after(2, 3);
+        assert!(r.iter(3).eq([0, 1, 2]));
+        assert!(r.round(0).eq([]));
+        assert!(r.round(0).eq([]));
+    }
+}
@@ -0,0 +1,250 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{rotate::Rotation, PinArray};
+
+/// A fixed capacity set of futures which yields their outputs in completion order
+///
+/// This is an allocation free equivalent of `FuturesUnordered`. Futures are pushed into free slots
+/// of the set while it is pinned and are never moved, once one completes its slot is freed for
+/// reuse.
+///
+/// ```
+/// # use core::{future::ready, pin::pin, task::{Context, Poll, Waker}};
+/// # use pin_array::PinFutureSet;
+/// let mut set = pin!(PinFutureSet::<_, 2>::new());
+/// set.as_mut().try_push(ready(1)).unwrap();
+/// set.as_mut().try_push(ready(2)).unwrap();
+/// assert!(set.as_mut().try_push(ready(3)).is_err());
+///
+/// let mut cx = Context::from_waker(Waker::noop());
+/// assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+/// set.as_mut().try_push(ready(3)).unwrap();
+/// assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(Some(2)));
+/// assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(Some(3)));
+/// assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(None));
+/// ```
+#[must_use = "futures do nothing unless polled"]
+pub struct PinFutureSet<F, const N: usize> {
+    futs: PinArray<Option<F>, N>,
+    len: usize,
+    rotation: Rotation,
+}
+
+impl<F, const N: usize> Default for PinFutureSet<F, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F, const N: usize> PinFutureSet<F, N> {
+    /// Create a new empty set
+    pub fn new() -> Self {
+        Self {
+            futs: PinArray::new([const { None }; N]),
+            len: 0,
+            rotation: Rotation::new(),
+        }
+    }
+
+    /// Get the number of futures in the set
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no futures in the set
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the maximum number of futures the set can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Check if every slot in the set is in use
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Attempt to push a future into a free slot, returning it back if the set is full
+    ///
+    /// The future is written directly into its slot and is not moved again
+    pub fn try_push(self: Pin<&mut Self>, fut: F) -> Result<(), F> {
+        if self.is_full() {
+            return Err(fut);
+        }
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut futs = unsafe { Pin::new_unchecked(&mut this.futs) };
+        let mut slot = futs
+            .as_mut()
+            .iter_mut()
+            .find(|s| s.is_none())
+            .expect("len is less than N so there is a free slot");
+        slot.set(Some(fut));
+        this.len += 1;
+        Ok(())
+    }
+}
+
+impl<F: Future, const N: usize> PinFutureSet<F, N> {
+    /// Poll the futures in the set, returning the output of the next one to complete
+    ///
+    /// Returns `Ready(None)` if the set is empty. Each call starts polling from the slot after
+    /// the one the previous call started from, and completed futures are dropped in place to free
+    /// up their slot.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<F::Output>> {
+        if self.is_empty() {
+            return Poll::Ready(None);
+        }
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut futs = unsafe { Pin::new_unchecked(&mut this.futs) };
+        for i in this.rotation.round(N) {
+            let mut slot = futs.as_mut().get_pin(i).unwrap();
+            let Some(f) = slot.as_mut().as_pin_mut() else {
+                continue;
+            };
+            if let Poll::Ready(v) = f.poll(cx) {
+                slot.set(None);
+                this.len -= 1;
+                return Poll::Ready(Some(v));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl<F: Future, const N: usize> futures_core::Stream for PinFutureSet<F, N> {
+    type Item = F::Output;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        PinFutureSet::poll_next(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        cell::Cell,
+        future::Future,
+        marker::PhantomPinned,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use crate::{
+        future::tests::{Countdown, Gate},
+        PinFutureSet,
+    };
+
+    #[test]
+    fn completion_order() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut set = pin!(PinFutureSet::<_, 3>::new());
+        for (n, v) in [(2, 'a'), (0, 'b'), (1, 'c')] {
+            assert!(set.as_mut().try_push(Countdown::new(n, v)).is_ok());
+        }
+        assert!(set.is_full());
+        let mut out = ['_'; 3];
+        let mut n = 0;
+        loop {
+            match set.as_mut().poll_next(&mut cx) {
+                Poll::Ready(Some(v)) => {
+                    out[n] = v;
+                    n += 1;
+                    assert_eq!(set.len(), 3 - n);
+                }
+                Poll::Ready(None) => break,
+                Poll::Pending => {}
+            }
+        }
+        assert_eq!(out, ['b', 'c', 'a']);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn full_returns_future() {
+        let mut set = pin!(PinFutureSet::<_, 1>::new());
+        assert!(set.as_mut().try_push(Countdown::new(0, 1)).is_ok());
+        let rejected = set.as_mut().try_push(Countdown::new(0, 2)).unwrap_err();
+        assert_eq!(rejected.value, Some(2));
+
+        let mut empty = pin!(PinFutureSet::<Countdown<u8>, 0>::new());
+        assert!(empty.as_mut().try_push(Countdown::new(0, 1)).is_err());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(empty.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    /// Future which records its address each time it is polled
+    struct Addr<'a> {
+        addr: &'a Cell<usize>,
+        ready: &'a Cell<bool>,
+        _p: PhantomPinned,
+    }
+
+    impl Future for Addr<'_> {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            self.addr.set(core::ptr::from_ref(&*self).addr());
+            if self.ready.get() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn slots_reused_without_moving() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let addrs = [Cell::new(0), Cell::new(0), Cell::new(0)];
+        let ready = [Cell::new(false), Cell::new(true), Cell::new(false)];
+        let mut set = pin!(PinFutureSet::<_, 2>::new());
+        for i in 0..2 {
+            let _ = set.as_mut().try_push(Addr {
+                addr: &addrs[i],
+                ready: &ready[i],
+                _p: PhantomPinned,
+            });
+        }
+        assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(Some(())));
+        let live = addrs[0].get();
+        assert!(set
+            .as_mut()
+            .try_push(Addr {
+                addr: &addrs[2],
+                ready: &ready[2],
+                _p: PhantomPinned,
+            })
+            .is_ok());
+        assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Pending);
+        assert_eq!(addrs[0].get(), live);
+        // the new future took over the freed slot
+        assert_eq!(addrs[2].get(), addrs[1].get());
+    }
+
+    #[test]
+    fn fair_polling() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let polls = [Cell::new(0), Cell::new(0)];
+        let ready = Cell::new(true);
+        let mut set = pin!(PinFutureSet::<_, 2>::new());
+        for p in &polls {
+            let _ = set.as_mut().try_push(Gate::new(p, &ready));
+        }
+        assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+        let _ = set.as_mut().try_push(Gate::new(&polls[0], &ready));
+        // the replacement in slot 0 is not polled before the future in slot 1
+        assert_eq!(set.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(polls.each_ref().map(Cell::get), [1, 1]);
+    }
+}
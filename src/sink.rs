@@ -0,0 +1,302 @@
+//! Combinators for [`PinArray`]s of sinks
+//!
+//! This requires the `futures-sink` feature
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_sink::Sink;
+
+use crate::{rotate::Rotation, PinArray};
+
+/// Poll every sink with `f`, resolving once all of them are ready or with the first error
+fn poll_all<Si: Sink<Item>, Item, const N: usize>(
+    sinks: Pin<&mut PinArray<Si, N>>,
+    cx: &mut Context<'_>,
+    mut f: impl FnMut(Pin<&mut Si>, &mut Context<'_>) -> Poll<Result<(), Si::Error>>,
+) -> Poll<Result<(), Si::Error>> {
+    let mut ready = true;
+    for s in sinks.iter_mut() {
+        match f(s, cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => ready = false,
+        }
+    }
+    if ready {
+        Poll::Ready(Ok(()))
+    } else {
+        Poll::Pending
+    }
+}
+
+/// Sink for [`PinArray::broadcast`]
+///
+/// Sends a clone of every item to each of the sinks
+#[must_use = "sinks do nothing unless polled"]
+pub struct Broadcast<Si, const N: usize> {
+    sinks: PinArray<Si, N>,
+}
+
+/// Sink for [`PinArray::round_robin`]
+///
+/// Sends each item to a single sink, taking turns between them
+#[must_use = "sinks do nothing unless polled"]
+pub struct RoundRobin<Si, const N: usize> {
+    sinks: PinArray<Si, N>,
+    rotation: Rotation,
+    /// The sink which reported it was ready and will get the next item
+    ready: Option<usize>,
+}
+
+impl<Si, const N: usize> PinArray<Si, N> {
+    /// Combine the sinks in this array into one sink which sends every item to all of them
+    ///
+    /// The combined sink is only ready, flushed or closed once every sink is, and fails with the
+    /// first error from any of them
+    pub fn broadcast(self) -> Broadcast<Si, N> {
+        Broadcast { sinks: self }
+    }
+
+    /// Combine the sinks in this array into one sink which distributes the items between them
+    ///
+    /// Items go to the sinks in turn, except that sinks which are not ready are skipped so a
+    /// slow sink does not hold up the others. Flushing and closing apply to every sink.
+    pub fn round_robin(self) -> RoundRobin<Si, N> {
+        const { assert!(N > 0, "cannot distribute to an empty PinArray") };
+        RoundRobin {
+            sinks: self,
+            rotation: Rotation::new(),
+            ready: None,
+        }
+    }
+}
+
+impl<Si, const N: usize> Broadcast<Si, N> {
+    fn sinks(self: Pin<&mut Self>) -> Pin<&mut PinArray<Si, N>> {
+        unsafe { self.map_unchecked_mut(|s| &mut s.sinks) }
+    }
+}
+
+impl<Item: Clone, Si: Sink<Item>, const N: usize> Sink<Item> for Broadcast<Si, N> {
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        poll_all(self.sinks(), cx, Si::poll_ready)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let Some((last, rest)) = self.sinks().split_last_pin() else {
+            return Ok(());
+        };
+        for s in rest {
+            s.start_send(item.clone())?;
+        }
+        last.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        poll_all(self.sinks(), cx, Si::poll_flush)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        poll_all(self.sinks(), cx, Si::poll_close)
+    }
+}
+
+impl<Si, const N: usize> RoundRobin<Si, N> {
+    fn sinks(self: Pin<&mut Self>) -> Pin<&mut PinArray<Si, N>> {
+        self.project().0
+    }
+
+    /// Split into the pinned sinks and the unpinned rotation state
+    fn project(
+        self: Pin<&mut Self>,
+    ) -> (Pin<&mut PinArray<Si, N>>, &mut Rotation, &mut Option<usize>) {
+        let this = unsafe { self.get_unchecked_mut() };
+        (
+            unsafe { Pin::new_unchecked(&mut this.sinks) },
+            &mut this.rotation,
+            &mut this.ready,
+        )
+    }
+}
+
+impl<Item, Si: Sink<Item>, const N: usize> Sink<Item> for RoundRobin<Si, N> {
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let (mut sinks, rotation, ready) = self.project();
+        if ready.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        for i in rotation.iter(N) {
+            match sinks.as_mut().get_pin(i).unwrap().poll_ready(cx) {
+                Poll::Ready(Ok(())) => {
+                    *ready = Some(i);
+                    return Poll::Ready(Ok(()));
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => {}
+            }
+        }
+        Poll::Pending
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let (sinks, rotation, ready) = self.project();
+        let i = ready
+            .take()
+            .expect("start_send called without poll_ready returning Ready");
+        rotation.start_after(i, N);
+        sinks.get_pin(i).unwrap().start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        poll_all(self.sinks(), cx, Si::poll_flush)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        poll_all(self.sinks(), cx, Si::poll_close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        cell::Cell,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use futures_sink::Sink;
+
+    use crate::PinArray;
+
+    /// Sink which sums the items sent to it
+    struct Sum<'a> {
+        sum: &'a Cell<u32>,
+        busy: &'a Cell<bool>,
+        closed: bool,
+    }
+
+    impl<'a> Sum<'a> {
+        fn new(sum: &'a Cell<u32>, busy: &'a Cell<bool>) -> Self {
+            Self {
+                sum,
+                busy,
+                closed: false,
+            }
+        }
+    }
+
+    impl Sink<u32> for Sum<'_> {
+        type Error = &'static str;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            if self.closed {
+                Poll::Ready(Err("closed"))
+            } else if self.busy.get() {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: u32) -> Result<(), Self::Error> {
+            self.sum.set(self.sum.get() + item);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            if self.busy.get() {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn poll_close(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            let r = self.as_mut().poll_flush(cx);
+            if r.is_ready() {
+                self.closed = true;
+            }
+            r
+        }
+    }
+
+    fn send<Si: Sink<u32>>(mut s: Pin<&mut Si>, item: u32) -> Poll<Result<(), Si::Error>> {
+        let mut cx = Context::from_waker(Waker::noop());
+        match s.as_mut().poll_ready(&mut cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(s.start_send(item)),
+            other => other,
+        }
+    }
+
+    #[test]
+    fn broadcast_sends_to_all() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let sums = [Cell::new(0), Cell::new(0), Cell::new(0)];
+        let busy = [Cell::new(false), Cell::new(false), Cell::new(false)];
+        let mut b = pin!(PinArray::new(core::array::from_fn::<_, 3, _>(|i| {
+            Sum::new(&sums[i], &busy[i])
+        }))
+        .broadcast());
+        assert_eq!(send(b.as_mut(), 2), Poll::Ready(Ok(())));
+        assert_eq!(sums.each_ref().map(Cell::get), [2, 2, 2]);
+
+        busy[1].set(true);
+        assert_eq!(send(b.as_mut(), 3), Poll::Pending);
+        assert_eq!(b.as_mut().poll_flush(&mut cx), Poll::Pending);
+        busy[1].set(false);
+        assert_eq!(b.as_mut().poll_flush(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(b.as_mut().poll_close(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(send(b.as_mut(), 3), Poll::Ready(Err("closed")));
+        assert_eq!(sums.each_ref().map(Cell::get), [2, 2, 2]);
+    }
+
+    #[test]
+    fn round_robin_takes_turns() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let sums = [Cell::new(0), Cell::new(0), Cell::new(0)];
+        let busy = [Cell::new(false), Cell::new(false), Cell::new(false)];
+        let mut r = pin!(PinArray::new(core::array::from_fn::<_, 3, _>(|i| {
+            Sum::new(&sums[i], &busy[i])
+        }))
+        .round_robin());
+        for item in [1, 10, 100, 1000] {
+            assert_eq!(send(r.as_mut(), item), Poll::Ready(Ok(())));
+        }
+        assert_eq!(sums.each_ref().map(Cell::get), [1001, 10, 100]);
+
+        // busy sinks are skipped
+        busy[1].set(true);
+        assert_eq!(send(r.as_mut(), 1), Poll::Ready(Ok(())));
+        assert_eq!(send(r.as_mut(), 1), Poll::Ready(Ok(())));
+        assert_eq!(sums.each_ref().map(Cell::get), [1002, 10, 101]);
+        assert_eq!(r.as_mut().poll_flush(&mut cx), Poll::Pending);
+
+        for b in &busy {
+            b.set(true);
+        }
+        assert_eq!(send(r.as_mut(), 1), Poll::Pending);
+        for b in &busy {
+            b.set(false);
+        }
+        assert_eq!(r.as_mut().poll_close(&mut cx), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    #[should_panic]
+    fn round_robin_send_without_ready() {
+        let sum = Cell::new(0);
+        let busy = Cell::new(false);
+        let r = pin!(PinArray::new([Sum::new(&sum, &busy)]).round_robin());
+        let _ = r.start_send(1);
+    }
+}
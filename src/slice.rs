@@ -0,0 +1,609 @@
+use core::{
+    fmt,
+    marker::PhantomPinned,
+    ops::{Bound, Range, RangeBounds},
+    pin::Pin,
+};
+
+use crate::iter::{SliceIter, SliceIterMut, SliceIterPinRef};
+
+/// A dynamically sized [structurally pinned][structural pinning] slice of values
+///
+/// This is the unsized counterpart to [`PinArray`](crate::PinArray) and can be obtained from
+/// one with [`PinArray::as_slice`](crate::PinArray::as_slice) or
+/// [`PinArray::as_pin_slice`](crate::PinArray::as_pin_slice). Taking a `Pin<&mut PinSlice<T>>`
+/// allows code to accept pinned arrays of any length.
+///
+/// [structural pinning]: https://doc.rust-lang.org/std/pin/index.html#projections-and-structural-pinning
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(transparent)]
+pub struct PinSlice<T> {
+    _pin: PhantomPinned,
+    elements: [T],
+}
+
+impl<T> PinSlice<T> {
+    pub(crate) fn from_ref(elements: &[T]) -> &Self {
+        // SAFETY: `PinSlice` is `repr(transparent)` over `[T]`
+        unsafe { &*(elements as *const [T] as *const Self) }
+    }
+    pub(crate) fn from_mut(elements: &mut [T]) -> &mut Self {
+        // SAFETY: `PinSlice` is `repr(transparent)` over `[T]`
+        unsafe { &mut *(elements as *mut [T] as *mut Self) }
+    }
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut T {
+        self.elements.as_mut_ptr()
+    }
+
+    /// Get the length of the [`PinSlice`]
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// assert_eq!(PinArray::new(['a', 'b', 'c']).as_slice().len(), 3);
+    /// ```
+    pub const fn len(&self) -> usize {
+        self.elements.len()
+    }
+    /// Check if the slice is empty
+    ///
+    /// ```
+    /// # use pin_array::PinArray;
+    /// assert!(PinArray::<u32, 0>::new([]).as_slice().is_empty());
+    /// ```
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Attempt to get a reference to an element by index
+    ///
+    /// Note this does not require `Pin` as a reference is trivially
+    /// `Unpin`
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new([1, 2, 3]));
+    /// let s = p.as_slice();
+    /// assert_eq!(s.get(0), Some(&1));
+    /// assert_eq!(s.get(3), None);
+    /// ```
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.elements.get(idx)
+    }
+
+    /// Attempt to get a pinned reference to an element by index
+    ///
+    /// Note this requires `self` to be pinned
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let mut s = p.as_mut().as_pin_slice();
+    /// assert_eq!(s.as_mut().get_pin(0), Some(Pin::new(&mut 1)));
+    /// assert_eq!(s.as_mut().get_pin(3), None);
+    /// ```
+    pub fn get_pin(self: Pin<&mut Self>, idx: usize) -> Option<Pin<&mut T>> {
+        unsafe { self.get_unchecked_mut() }
+            .elements
+            .get_mut(idx)
+            .map(|e| unsafe { Pin::new_unchecked(e) })
+    }
+
+    /// Replace the element at `idx` with `value`, dropping the old element in place
+    ///
+    /// This is [`Pin::set`] for a single element. Gives `value` back if `idx` is out of bounds.
+    /// If dropping the old element panics, `value` has still been written to the slot so nothing
+    /// is dropped twice.
+    ///
+    /// ```
+    /// # use core::pin::pin;
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let mut s = p.as_mut().as_pin_slice();
+    /// assert_eq!(s.as_mut().set_pin(1, 5), Ok(()));
+    /// assert_eq!(s.as_mut().set_pin(3, 6), Err(6));
+    /// assert_eq!(s.get(1), Some(&5));
+    /// ```
+    pub fn set_pin(self: Pin<&mut Self>, idx: usize, value: T) -> Result<(), T> {
+        match self.get_pin(idx) {
+            Some(mut e) => {
+                e.set(value);
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Replace the element at `idx` with its default value, dropping the old element in place
+    ///
+    /// Returns `false` if `idx` is out of bounds, see [`PinSlice::set_pin`]
+    pub fn reset_pin(self: Pin<&mut Self>, idx: usize) -> bool
+    where
+        T: Default,
+    {
+        match self.get_pin(idx) {
+            Some(mut e) => {
+                e.set(T::default());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attempt to get a pinned shared reference to an element by index
+    ///
+    /// Unlike [`PinSlice::get`] this keeps the pinning guarantee, which is needed to call
+    /// `self: Pin<&Self>` methods on the element
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new([1, 2, 3]));
+    /// let s = p.as_ref().as_pin_ref_slice();
+    /// assert_eq!(s.get_pin_ref(1), Some(Pin::new(&2)));
+    /// assert_eq!(s.get_pin_ref(3), None);
+    /// ```
+    pub fn get_pin_ref(self: Pin<&Self>, idx: usize) -> Option<Pin<&T>> {
+        self.get_ref()
+            .elements
+            .get(idx)
+            .map(|e| unsafe { Pin::new_unchecked(e) })
+    }
+
+    /// Get pinned references to several distinct elements at once
+    ///
+    /// Fails if any index is out of bounds or if any two indices are the same
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::{GetManyPinError, PinArray};
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let mut s = p.as_mut().as_pin_slice();
+    /// let [a, b] = s.as_mut().get_many_pin([2, 0]).unwrap();
+    /// assert_eq!((*a, *b), (3, 1));
+    /// assert_eq!(
+    ///     s.as_mut().get_many_pin([0, 0]),
+    ///     Err(GetManyPinError::Overlapping { index: 0 })
+    /// );
+    /// ```
+    pub fn get_many_pin<const K: usize>(
+        self: Pin<&mut Self>,
+        indices: [usize; K],
+    ) -> Result<[Pin<&mut T>; K], GetManyPinError> {
+        let len = self.len();
+        for (i, &index) in indices.iter().enumerate() {
+            if index >= len {
+                return Err(GetManyPinError::OutOfBounds { index, len });
+            }
+            if indices[..i].contains(&index) {
+                return Err(GetManyPinError::Overlapping { index });
+            }
+        }
+        Ok(unsafe { self.get_many_pin_unchecked(indices) })
+    }
+
+    /// Get pinned references to several elements at once without checking the indices
+    ///
+    /// # Safety
+    /// Every index must be in bounds and no two indices may be the same. See
+    /// [`PinSlice::get_many_pin`] for a checked version
+    pub unsafe fn get_many_pin_unchecked<const K: usize>(
+        self: Pin<&mut Self>,
+        indices: [usize; K],
+    ) -> [Pin<&mut T>; K] {
+        let arr = unsafe { self.get_unchecked_mut().as_mut_ptr() };
+        core::array::from_fn(|i| {
+            let p = unsafe { arr.add(indices[i]) };
+            unsafe { Pin::new_unchecked(&mut *p) }
+        })
+    }
+
+    /// Attempt to get a pinned sub-slice covering `range`
+    ///
+    /// Accepts any of the range forms (`a..b`, `a..=b`, `..b`, `a..`, `..`) and returns `None`
+    /// if the range is out of bounds, in the same way as [`PinSlice::get_pin`]
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3, 4]));
+    /// let mut s = p.as_mut().as_pin_slice();
+    /// assert_eq!(s.as_mut().get_pin_range(1..3).unwrap().len(), 2);
+    /// assert_eq!(s.as_mut().get_pin_range(..=3).unwrap().len(), 4);
+    /// assert!(s.as_mut().get_pin_range(2..5).is_none());
+    /// ```
+    pub fn get_pin_range<R: RangeBounds<usize>>(
+        self: Pin<&mut Self>,
+        range: R,
+    ) -> Option<Pin<&mut Self>> {
+        let range = to_range(range, self.len())?;
+        let els = unsafe { &mut self.get_unchecked_mut().elements };
+        Some(unsafe { Pin::new_unchecked(Self::from_mut(&mut els[range])) })
+    }
+
+    /// Split this pinned slice into two disjoint pinned slices at `mid`
+    ///
+    /// The first slice contains the elements `[0, mid)` and the second `[mid, len)`
+    ///
+    /// # Panics
+    /// If `mid > len`
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let (l, r) = p.as_mut().as_pin_slice().split_at_pin(1);
+    /// assert_eq!(l.len(), 1);
+    /// assert_eq!(r.len(), 2);
+    /// ```
+    pub fn split_at_pin(self: Pin<&mut Self>, mid: usize) -> (Pin<&mut Self>, Pin<&mut Self>) {
+        let (l, r) = unsafe { self.get_unchecked_mut() }
+            .elements
+            .split_at_mut(mid);
+        unsafe {
+            (
+                Pin::new_unchecked(Self::from_mut(l)),
+                Pin::new_unchecked(Self::from_mut(r)),
+            )
+        }
+    }
+
+    /// Split off the first element, returning `None` if the slice is empty
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let (first, rest) = p.as_mut().as_pin_slice().split_first_pin().unwrap();
+    /// assert_eq!(first, Pin::new(&mut 1));
+    /// assert_eq!(rest.len(), 2);
+    /// ```
+    pub fn split_first_pin(self: Pin<&mut Self>) -> Option<(Pin<&mut T>, Pin<&mut Self>)> {
+        let (first, rest) = unsafe { self.get_unchecked_mut() }
+            .elements
+            .split_first_mut()?;
+        unsafe {
+            Some((
+                Pin::new_unchecked(first),
+                Pin::new_unchecked(Self::from_mut(rest)),
+            ))
+        }
+    }
+
+    /// Split off the last element, returning `None` if the slice is empty
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new([1, 2, 3]));
+    /// let (last, rest) = p.as_mut().as_pin_slice().split_last_pin().unwrap();
+    /// assert_eq!(last, Pin::new(&mut 3));
+    /// assert_eq!(rest.len(), 2);
+    /// ```
+    pub fn split_last_pin(self: Pin<&mut Self>) -> Option<(Pin<&mut T>, Pin<&mut Self>)> {
+        let (last, rest) = unsafe { self.get_unchecked_mut() }
+            .elements
+            .split_last_mut()?;
+        unsafe {
+            Some((
+                Pin::new_unchecked(last),
+                Pin::new_unchecked(Self::from_mut(rest)),
+            ))
+        }
+    }
+
+    /// Get an iterator over references to the elements
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new(['h', 'i']));
+    /// let mut i = p.as_slice().iter();
+    /// assert_eq!(i.next(), Some(&'h'));
+    /// assert_eq!(i.next(), Some(&'i'));
+    /// assert_eq!(i.next(), None);
+    /// ```
+    pub fn iter(&self) -> SliceIter<'_, T> {
+        SliceIter::new(self)
+    }
+
+    /// Get an iterator over pinned shared references to the elements
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let p = pin!(PinArray::new(['h', 'i']));
+    /// let mut i = p.as_ref().as_pin_ref_slice().iter_pin_ref();
+    /// assert_eq!(i.next(), Some(Pin::new(&'h')));
+    /// assert_eq!(i.next(), Some(Pin::new(&'i')));
+    /// assert_eq!(i.next(), None);
+    /// ```
+    pub fn iter_pin_ref(self: Pin<&Self>) -> SliceIterPinRef<'_, T> {
+        SliceIterPinRef::new(self)
+    }
+
+    /// Get an iterator over pinned mutable references to the elements
+    ///
+    /// ```
+    /// # use core::pin::{pin, Pin};
+    /// # use pin_array::PinArray;
+    /// let mut p = pin!(PinArray::new(['h', 'i']));
+    /// let mut i = p.as_mut().as_pin_slice().iter_mut();
+    /// assert_eq!(i.next(), Some(Pin::new(&mut 'h')));
+    /// assert_eq!(i.next(), Some(Pin::new(&mut 'i')));
+    /// assert_eq!(i.next(), None);
+    /// ```
+    pub fn iter_mut(self: Pin<&mut Self>) -> SliceIterMut<'_, T> {
+        SliceIterMut::new(unsafe { self.get_unchecked_mut() })
+    }
+}
+
+impl<T: Unpin> Unpin for PinSlice<T> {}
+
+impl<'a, T> IntoIterator for &'a PinSlice<T> {
+    type Item = &'a T;
+    type IntoIter = SliceIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for Pin<&'a PinSlice<T>> {
+    type Item = Pin<&'a T>;
+    type IntoIter = SliceIterPinRef<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_pin_ref()
+    }
+}
+
+impl<'a, T> IntoIterator for Pin<&'a mut PinSlice<T>> {
+    type Item = Pin<&'a mut T>;
+    type IntoIter = SliceIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Error returned by [`PinSlice::get_many_pin`] and [`PinArray::get_many_pin`](crate::PinArray::get_many_pin)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GetManyPinError {
+    /// `index` was not less than the length `len`
+    OutOfBounds { index: usize, len: usize },
+    /// `index` was requested more than once
+    Overlapping { index: usize },
+}
+
+impl fmt::Display for GetManyPinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { index, len } => {
+                write!(f, "index {index} out of bounds for length {len}")
+            }
+            Self::Overlapping { index } => write!(f, "index {index} requested more than once"),
+        }
+    }
+}
+
+impl core::error::Error for GetManyPinError {}
+
+/// Resolve `range` against a slice of length `len`, returning `None` if it is out of bounds
+fn to_range<R: RangeBounds<usize>>(range: R, len: usize) -> Option<Range<usize>> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.checked_add(1)?,
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    (start <= end && end <= len).then_some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{marker::PhantomPinned, pin::pin};
+
+    use crate::{GetManyPinError, PinArray, PinSlice};
+
+    fn sum(s: core::pin::Pin<&mut PinSlice<u32>>) -> u32 {
+        s.into_iter().map(|e| *e).sum()
+    }
+
+    #[test]
+    fn accepts_any_length() {
+        let mut a = pin!(PinArray::new([1, 2, 3]));
+        let mut b = pin!(PinArray::new([4, 5]));
+        assert_eq!(sum(a.as_mut().as_pin_slice()), 6);
+        assert_eq!(sum(b.as_mut().into()), 9);
+    }
+
+    #[test]
+    fn get_pin_not_unpin() {
+        let mut p = pin!(PinArray::new([PhantomPinned, PhantomPinned]));
+        let mut s = p.as_mut().as_pin_slice();
+        assert_eq!(s.len(), 2);
+        assert!(s.as_mut().get_pin(1).is_some());
+        assert!(s.as_mut().get_pin(2).is_none());
+    }
+
+    #[test]
+    fn set_pin_in_place() {
+        use core::cell::Cell;
+
+        use crate::tests::{panics, CountDrop};
+
+        let drops = Cell::new(0);
+        let new = |id, panic| CountDrop::new(&drops, id, panic);
+        {
+            let mut p = pin!(PinArray::new([new(0, false), new(1, true)]));
+            let mut s = p.as_mut().as_pin_slice();
+            assert!(s.as_mut().set_pin(0, new(2, false)).is_ok());
+            assert_eq!(drops.get(), 1);
+            drop(s.as_mut().set_pin(2, new(3, false)).unwrap_err());
+            assert_eq!(drops.get(), 2);
+
+            // the old element panicking on drop still leaves the new one in its place
+            assert!(panics(|| s.as_mut().set_pin(1, new(4, false))));
+            assert_eq!(drops.get(), 3);
+            assert_eq!(s.iter().map(|e| e.id).sum::<usize>(), 6);
+        }
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn reset_pin() {
+        let mut p = pin!(PinArray::new([Some(1), Some(2)]));
+        assert!(p.as_mut().reset_pin(1));
+        assert!(!p.as_mut().reset_pin(2));
+        assert_eq!(p.as_ref_array(), [&Some(1), &None]);
+    }
+
+    #[test]
+    fn get_pin_range_bounds() {
+        use core::ops::Bound;
+
+        let mut p = pin!(PinArray::new([0, 1, 2, 3, 4, 5]));
+        let mut s = p.as_mut().as_pin_slice();
+        let sub = s.as_mut().get_pin_range(2..4).unwrap();
+        assert_eq!(sub.len(), 2);
+        assert_eq!(sub.get(0), Some(&2));
+        assert_eq!(sub.get(1), Some(&3));
+        assert_eq!(s.as_mut().get_pin_range(..).unwrap().len(), 6);
+        assert_eq!(s.as_mut().get_pin_range(6..).unwrap().len(), 0);
+        assert_eq!(s.as_mut().get_pin_range(..=5).unwrap().len(), 6);
+        assert_eq!(
+            s.as_mut()
+                .get_pin_range((Bound::Excluded(1), Bound::Included(2)))
+                .unwrap()
+                .get(0),
+            Some(&2)
+        );
+        assert!(s.as_mut().get_pin_range(..=6).is_none());
+        assert!(s.as_mut().get_pin_range(7..).is_none());
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = 4..2;
+        assert!(s.as_mut().get_pin_range(backwards).is_none());
+        assert!(s
+            .as_mut()
+            .get_pin_range((Bound::Excluded(usize::MAX), Bound::Unbounded))
+            .is_none());
+    }
+
+    #[test]
+    fn get_pin_range_mutate() {
+        let mut p = pin!(PinArray::new([1, 2, 3, 4]));
+        {
+            let lo = p.as_mut().get_pin_range(0..2).unwrap();
+            for mut e in lo.iter_mut() {
+                *e += 10;
+            }
+        }
+        let hi = p.as_mut().get_pin_range(2..).unwrap();
+        for mut e in hi.iter_mut() {
+            *e += 20;
+        }
+        assert_eq!(p.as_ref_array(), [&11, &12, &23, &24]);
+    }
+
+    // these are mostly here to check that the splits don't cause UB according to MIRI
+    #[test]
+    fn split_at_pin_disjoint_ub() {
+        let mut p = pin!(PinArray::new([1, 2, 3, 4]));
+        let (mut l, mut r) = p.as_mut().as_pin_slice().split_at_pin(2);
+        let mut a = l.as_mut().get_pin(1).unwrap();
+        let mut b = r.as_mut().get_pin(0).unwrap();
+        *a += 10;
+        *b += 20;
+        assert_eq!((*a, *b), (12, 23));
+        assert_eq!(p.as_ref_array(), [&1, &12, &23, &4]);
+    }
+
+    #[test]
+    fn split_at_pin_ends() {
+        let mut p = pin!(PinArray::new([1, 2]));
+        let (l, r) = p.as_mut().as_pin_slice().split_at_pin(0);
+        assert_eq!((l.len(), r.len()), (0, 2));
+        let (l, r) = p.as_mut().as_pin_slice().split_at_pin(2);
+        assert_eq!((l.len(), r.len()), (2, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_at_pin_out_of_bounds() {
+        let mut p = pin!(PinArray::new([1, 2]));
+        let _ = p.as_mut().as_pin_slice().split_at_pin(3);
+    }
+
+    fn sum_recursive(s: core::pin::Pin<&mut PinSlice<u32>>) -> u32 {
+        match s.split_first_pin() {
+            Some((first, rest)) => *first + sum_recursive(rest),
+            None => 0,
+        }
+    }
+
+    #[test]
+    fn split_first_pin_recursive() {
+        let mut p = pin!(PinArray::new([1, 2, 3, 4]));
+        assert_eq!(sum_recursive(p.as_mut().as_pin_slice()), 10);
+    }
+
+    #[test]
+    fn split_last_pin_ub() {
+        let mut p = pin!(PinArray::new([
+            NotUnpin(1, PhantomPinned),
+            NotUnpin(2, PhantomPinned),
+            NotUnpin(3, PhantomPinned),
+        ]));
+        let (last, mut rest) = p.as_mut().as_pin_slice().split_last_pin().unwrap();
+        let first = rest.as_mut().get_pin(0).unwrap();
+        assert_eq!(last.0, 3);
+        assert_eq!(first.0, 1);
+        assert!(pin!(PinArray::<u32, 0>::new([]))
+            .as_pin_slice()
+            .split_last_pin()
+            .is_none());
+    }
+
+    struct NotUnpin(u8, PhantomPinned);
+
+    #[test]
+    fn get_many_pin_disjoint_ub() {
+        let mut p = pin!(PinArray::new([1, 2, 3, 4]));
+        let [mut a, mut b, mut c] = p.as_mut().as_pin_slice().get_many_pin([3, 0, 2]).unwrap();
+        *a += 10;
+        *b += 20;
+        *c += 30;
+        assert_eq!((*a, *b, *c), (14, 21, 33));
+        assert_eq!(p.as_ref_array(), [&21, &2, &33, &14]);
+    }
+
+    #[test]
+    fn get_many_pin_errors() {
+        let mut p = pin!(PinArray::new([1, 2, 3]));
+        let mut s = p.as_mut().as_pin_slice();
+        assert_eq!(
+            s.as_mut().get_many_pin([0, 3]).unwrap_err(),
+            GetManyPinError::OutOfBounds { index: 3, len: 3 }
+        );
+        assert_eq!(
+            s.as_mut().get_many_pin([1, 2, 1]).unwrap_err(),
+            GetManyPinError::Overlapping { index: 1 }
+        );
+        assert_eq!(s.as_mut().get_many_pin([]), Ok([]));
+    }
+}
+
+#[cfg(test)]
+mod impl_tests {
+    use super::*;
+    use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+    assert_impl_all!(PinSlice<u32>: Unpin);
+    assert_not_impl_all!(PinSlice<PhantomPinned>: Unpin);
+}
@@ -0,0 +1,197 @@
+//! Combinators for [`PinArray`]s of streams
+//!
+//! This requires the `futures-core` feature
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::{FusedStream, Stream};
+
+use crate::{rotate::Rotation, PinArray};
+
+/// Stream for [`PinArray::merge`]
+///
+/// Yields the items of all the streams tagged with the index of the stream they came from
+#[must_use = "streams do nothing unless polled"]
+pub struct Merge<S, const N: usize> {
+    streams: PinArray<S, N>,
+    done: [bool; N],
+    rotation: Rotation,
+}
+
+impl<S: Stream, const N: usize> PinArray<S, N> {
+    /// Merge all the streams in this array into one stream
+    ///
+    /// Items are yielded as `(index, item)` as soon as any stream produces one, with each poll
+    /// starting from the stream after the one the previous poll started from. The merged stream
+    /// only finishes once every stream has finished.
+    ///
+    /// ```
+    /// # use core::{pin::pin, task::{Context, Poll, Waker}};
+    /// # use futures_core::Stream;
+    /// # use pin_array::PinArray;
+    /// # struct Once(Option<u32>);
+    /// # impl Stream for Once {
+    /// #     type Item = u32;
+    /// #     fn poll_next(mut self: core::pin::Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+    /// #         Poll::Ready(self.0.take())
+    /// #     }
+    /// # }
+    /// let mut m = pin!(PinArray::new([Once(Some(1)), Once(Some(2))]).merge());
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(Some((0, 1))));
+    /// assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(Some((1, 2))));
+    /// assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    /// ```
+    pub fn merge(self) -> Merge<S, N> {
+        Merge {
+            streams: self,
+            done: [false; N],
+            rotation: Rotation::new(),
+        }
+    }
+}
+
+impl<S: Stream, const N: usize> Stream for Merge<S, N> {
+    type Item = (usize, S::Item);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut streams = unsafe { Pin::new_unchecked(&mut this.streams) };
+        for i in this.rotation.round(N) {
+            if this.done[i] {
+                continue;
+            }
+            match streams.as_mut().get_pin(i).unwrap().poll_next(cx) {
+                Poll::Ready(Some(v)) => return Poll::Ready(Some((i, v))),
+                Poll::Ready(None) => this.done[i] = true,
+                Poll::Pending => {}
+            }
+        }
+        if this.is_terminated() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.streams
+            .iter()
+            .zip(self.done)
+            .filter(|(_, done)| !done)
+            .fold((0, Some(0)), |(lo, hi), (s, _)| {
+                let (s_lo, s_hi) = s.size_hint();
+                (
+                    lo.saturating_add(s_lo),
+                    hi.zip(s_hi).and_then(|(a, b)| a.checked_add(b)),
+                )
+            })
+    }
+}
+
+impl<S: Stream, const N: usize> FusedStream for Merge<S, N> {
+    fn is_terminated(&self) -> bool {
+        self.done.iter().all(|d| *d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use futures_core::{FusedStream, Stream};
+
+    use crate::PinArray;
+
+    /// Stream which yields `0..len`, returning `Pending` before each item when `slow` is set
+    struct Counter {
+        i: u32,
+        len: u32,
+        slow: bool,
+        stalled: bool,
+    }
+
+    impl Counter {
+        fn new(len: u32, slow: bool) -> Self {
+            Self {
+                i: 0,
+                len,
+                slow,
+                stalled: false,
+            }
+        }
+    }
+
+    impl Stream for Counter {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+            if self.i >= self.len {
+                return Poll::Ready(None);
+            }
+            if self.slow && !self.stalled {
+                self.stalled = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.stalled = false;
+            self.i += 1;
+            Poll::Ready(Some(self.i - 1))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let n = (self.len - self.i) as usize;
+            (n, Some(n))
+        }
+    }
+
+    #[test]
+    fn merge_is_fair() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut m = pin!(PinArray::new([
+            Counter::new(3, false),
+            Counter::new(3, false),
+            Counter::new(1, false),
+        ])
+        .merge());
+        assert_eq!(m.size_hint(), (7, Some(7)));
+        let mut items = [(0, 0); 7];
+        for item in &mut items {
+            match m.as_mut().poll_next(&mut cx) {
+                Poll::Ready(Some(v)) => *item = v,
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        assert_eq!(
+            items,
+            [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (0, 2), (1, 2)]
+        );
+        assert!(!m.is_terminated());
+        assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(None));
+        assert!(m.is_terminated());
+        assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn merge_waits_for_all() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut m = pin!(PinArray::new([Counter::new(0, false), Counter::new(1, true)]).merge());
+        assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Pending);
+        assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(Some((1, 0))));
+        assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn merge_empty() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut m = pin!(PinArray::<Counter, 0>::new([]).merge());
+        assert!(m.is_terminated());
+        assert_eq!(m.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    }
+}
@@ -0,0 +1,338 @@
+//! A growable collection whose elements never move
+//!
+//! This requires the `alloc` feature
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, iter::FusedIterator, mem::MaybeUninit, pin::Pin};
+
+/// Capacity of the first chunk, every chunk after it is twice the size of the one before
+const FIRST_CHUNK: usize = 4;
+
+/// A growable vector whose elements are pinned in place once they are pushed
+///
+/// The elements are stored in a list of heap allocated chunks which double in size as the vector
+/// grows. Chunks are never reallocated, so unlike `Vec` pushing never moves the existing
+/// elements. Because the elements live behind their own allocations the `PinVec` itself is
+/// `Unpin` and can be freely moved, much like `Pin<Box<T>>`.
+///
+/// ```
+/// # use pin_array::PinVec;
+/// let mut v = PinVec::new();
+/// let first = core::ptr::from_ref(&*v.push(1));
+/// for i in 2..100 {
+///     v.push(i);
+/// }
+/// assert_eq!(core::ptr::from_ref(v.get(0).unwrap()), first);
+/// assert_eq!(v.iter().sum::<i32>(), 4950);
+/// ```
+pub struct PinVec<T> {
+    chunks: Vec<Box<[MaybeUninit<T>]>>,
+    len: usize,
+}
+
+impl<T> Default for PinVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PinVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for PinVec<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Split an index into the chunk it is in and its offset within that chunk
+fn locate(idx: usize) -> (usize, usize) {
+    let chunk = (idx / FIRST_CHUNK + 1).ilog2() as usize;
+    (chunk, idx - FIRST_CHUNK * ((1 << chunk) - 1))
+}
+
+/// Drop the first `len` elements across `chunks` in place, carrying on with the rest if one of
+/// them panics
+///
+/// # Safety
+/// The first `len` elements must be initialised and must not be used again
+unsafe fn drop_chunks<T>(chunks: &mut [Box<[MaybeUninit<T>]>], len: usize) {
+    struct Rest<'a, T>(&'a mut [Box<[MaybeUninit<T>]>], usize);
+    impl<T> Drop for Rest<'_, T> {
+        fn drop(&mut self) {
+            unsafe { drop_chunks(self.0, self.1) }
+        }
+    }
+
+    let Some((first, rest)) = chunks.split_first_mut() else {
+        return;
+    };
+    let n = len.min(first.len());
+    let _rest = Rest(rest, len - n);
+    unsafe { core::ptr::drop_in_place(core::ptr::from_mut(&mut first[..n]) as *mut [T]) };
+}
+
+impl<T> PinVec<T> {
+    /// Create a new empty `PinVec`, this does not allocate
+    pub const fn new() -> Self {
+        Self {
+            chunks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Get the number of elements
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no elements
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of elements which can be pushed without allocating a new chunk
+    pub fn capacity(&self) -> usize {
+        FIRST_CHUNK * ((1 << self.chunks.len()) - 1)
+    }
+
+    /// Push an element onto the end, returning a pinned reference to it
+    ///
+    /// The element stays at the same address until it is dropped by [`PinVec::clear`] or by
+    /// dropping the `PinVec`
+    pub fn push(&mut self, value: T) -> Pin<&mut T> {
+        let (chunk, offset) = locate(self.len);
+        if chunk == self.chunks.len() {
+            self.chunks
+                .push(Box::new_uninit_slice(FIRST_CHUNK << chunk));
+        }
+        let slot = self.chunks[chunk][offset].write(value);
+        self.len += 1;
+        unsafe { Pin::new_unchecked(slot) }
+    }
+
+    /// Attempt to get a reference to an element by index
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        if idx >= self.len {
+            return None;
+        }
+        let (chunk, offset) = locate(idx);
+        Some(unsafe { self.chunks[chunk][offset].assume_init_ref() })
+    }
+
+    /// Attempt to get a pinned reference to an element by index
+    pub fn get_pin(&mut self, idx: usize) -> Option<Pin<&mut T>> {
+        if idx >= self.len {
+            return None;
+        }
+        let (chunk, offset) = locate(idx);
+        Some(unsafe { Pin::new_unchecked(self.chunks[chunk][offset].assume_init_mut()) })
+    }
+
+    /// Get an iterator over references to the elements
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            chunks: self.chunks.iter(),
+            chunk: [].iter(),
+            remaining: self.len,
+        }
+    }
+
+    /// Get an iterator over pinned references to the elements
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            chunks: self.chunks.iter_mut(),
+            chunk: [].iter_mut(),
+            remaining: self.len,
+        }
+    }
+
+    /// Drop every element in place
+    ///
+    /// The chunks are kept so pushing afterwards reuses them. If dropping an element panics the
+    /// rest are still dropped.
+    pub fn clear(&mut self) {
+        let len = core::mem::replace(&mut self.len, 0);
+        unsafe { drop_chunks(&mut self.chunks, len) };
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PinVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut PinVec<T> {
+    type Item = Pin<&'a mut T>;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over references to the elements of a [`PinVec`]
+pub struct Iter<'a, T> {
+    chunks: core::slice::Iter<'a, Box<[MaybeUninit<T>]>>,
+    chunk: core::slice::Iter<'a, MaybeUninit<T>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        loop {
+            if let Some(e) = self.chunk.next() {
+                self.remaining -= 1;
+                return Some(unsafe { e.assume_init_ref() });
+            }
+            self.chunk = self.chunks.next()?.iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Iterator over pinned references to the elements of a [`PinVec`]
+pub struct IterMut<'a, T> {
+    chunks: core::slice::IterMut<'a, Box<[MaybeUninit<T>]>>,
+    chunk: core::slice::IterMut<'a, MaybeUninit<T>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = Pin<&'a mut T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        loop {
+            if let Some(e) = self.chunk.next() {
+                self.remaining -= 1;
+                return Some(unsafe { Pin::new_unchecked(e.assume_init_mut()) });
+            }
+            self.chunk = self.chunks.next()?.iter_mut();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use core::{cell::Cell, marker::PhantomPinned, pin::Pin};
+
+    use super::{locate, PinVec};
+    use crate::tests::{panics, CountDrop};
+
+    #[test]
+    fn locate_chunks() {
+        assert_eq!(locate(0), (0, 0));
+        assert_eq!(locate(3), (0, 3));
+        assert_eq!(locate(4), (1, 0));
+        assert_eq!(locate(11), (1, 7));
+        assert_eq!(locate(12), (2, 0));
+        assert_eq!(locate(27), (2, 15));
+        assert_eq!(locate(28), (3, 0));
+    }
+
+    #[test]
+    fn addresses_stable() {
+        let mut v = PinVec::new();
+        let mut addrs = [0; 50];
+        for (i, a) in addrs.iter_mut().enumerate() {
+            *a = core::ptr::from_ref(&*v.push((i, PhantomPinned))).addr();
+        }
+        assert_eq!(v.len(), 50);
+        assert_eq!(v.capacity(), 60);
+        for (i, a) in addrs.iter().enumerate() {
+            let e = v.get_pin(i).unwrap();
+            assert_eq!(e.0, i);
+            assert_eq!(core::ptr::from_ref(&*e).addr(), *a);
+        }
+        assert!(v.get(50).is_none());
+        assert!(v.get_pin(50).is_none());
+    }
+
+    #[test]
+    fn iterate() {
+        let mut v = PinVec::new();
+        assert_eq!(v.iter().next(), None);
+        for i in 0..13 {
+            v.push(i);
+        }
+        let mut i = v.iter();
+        assert_eq!(i.len(), 13);
+        assert!(i.by_ref().copied().eq(0..13));
+        assert_eq!(i.next(), None);
+
+        for mut e in &mut v {
+            *e *= 2;
+        }
+        assert!(v.iter().copied().eq((0..13).map(|e| e * 2)));
+    }
+
+    #[test]
+    fn clear_drops_in_place() {
+        let drops = Cell::new(0);
+        let mut v = PinVec::new();
+        for _ in 0..10 {
+            v.push(CountDrop::new(&drops, 0, false));
+        }
+        v.clear();
+        assert_eq!(drops.get(), 10);
+        assert!(v.is_empty());
+
+        // chunks are reused
+        v.push(CountDrop::new(&drops, 0, false));
+        assert_eq!(v.capacity(), 12);
+        drop(v);
+        assert_eq!(drops.get(), 11);
+    }
+
+    #[test]
+    fn clear_continues_after_panic() {
+        let drops = Cell::new(0);
+        let mut v = PinVec::new();
+        for i in 0..10 {
+            v.push(CountDrop::new(&drops, i, i == 2));
+        }
+        assert!(panics(|| v.clear()));
+        assert_eq!(drops.get(), 10);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut v = PinVec::new();
+        for _ in 0..100 {
+            let _: Pin<&mut ()> = v.push(());
+        }
+        assert_eq!(v.iter().count(), 100);
+    }
+
+    static_assertions::assert_impl_all!(PinVec<PhantomPinned>: Unpin, Send, Sync);
+    static_assertions::assert_not_impl_any!(PinVec<Cell<u8>>: Sync);
+}